@@ -1,4 +1,7 @@
-use parsebuf::{FallBack as Or, InwardStrategy as Strat, ParseCursor, PatternLoc as Loc};
+use parsebuf::{
+    Balanced, Context, CursorError, FallBack as Or, InwardStrategy as Strat, ParseCursor,
+    PatternLoc as Loc,
+};
 use stable_string_patterns_method::WhiteSpace;
 
 #[derive(Debug, PartialEq)]
@@ -9,12 +12,12 @@ struct ErrorLog<'a> {
     loc: Option<(u64, u64)>,
 }
 
-fn parse(input: &'_ str) -> Option<ErrorLog<'_>> {
+fn parse(input: &'_ str) -> Result<ErrorLog<'_>, CursorError> {
     let mut cursor = ParseCursor::new_empty_start(input);
 
     cursor
         .back_forward("ERROR:", Loc::BeginningOnce, Strat::WholeData)
-        .ok()?;
+        .context("parsing error prefix")?;
 
     cursor.front_forward_or("(", Loc::FirstExcluded, Or::ToTheEnd);
     let msg = cursor.cursor().trim();
@@ -22,11 +25,11 @@ fn parse(input: &'_ str) -> Option<ErrorLog<'_>> {
 
     let mut parens = cursor.iter_steps(|c| {
         c.back_forward('(', Loc::FirstIncluded, Strat::WholeData)?;
-        c.front_forward(')', Loc::FirstExcluded)
+        c.front_forward(Balanced::new('(', ')'), Loc::FirstExcluded)
     });
 
     let Some(first_par) = parens.next() else {
-        return Some(ErrorLog {
+        return Ok(ErrorLog {
             msg,
             hint: None,
             file: None,
@@ -39,19 +42,20 @@ fn parse(input: &'_ str) -> Option<ErrorLog<'_>> {
     let hint;
     if first_par.starts_with('/') {
         let mut first_par = ParseCursor::new_empty_start(first_par);
-        let file_val = first_par
-            .step(|c| c.front_forward(WhiteSpace, Loc::FirstExcluded))
-            .unwrap();
+        let file_val = first_par.step_context("parsing file path", |c| {
+            c.front_forward(WhiteSpace, Loc::FirstExcluded)
+        })?;
         file = Some(file_val);
-        let parse_num_prefix = |c: &mut ParseCursor, pref| {
-            c.back_forward(pref, Loc::FirstIncluded, Strat::WholeData)
-                .unwrap()
-                .front_forward(|c: char| c.is_ascii_digit(), Loc::BeginningMany)
-                .unwrap();
-            c.cursor().parse().unwrap()
+        let parse_num_prefix = |c: &mut ParseCursor, pref| -> Result<u64, CursorError> {
+            c.back_forward(pref, Loc::FirstIncluded, Strat::WholeData)?
+                .take()
         };
-        let line = parse_num_prefix(&mut first_par, "at line ");
-        let column = parse_num_prefix(&mut first_par, ", column ");
+        let line = parse_num_prefix(&mut first_par, "at line ")
+            .context("parsing line number")
+            .context("parsing location inside first parenthesis")?;
+        let column = parse_num_prefix(&mut first_par, ", column ")
+            .context("parsing column number")
+            .context("parsing location inside first parenthesis")?;
         loc = Some((line, column));
         hint = parens.next();
     } else {
@@ -59,7 +63,7 @@ fn parse(input: &'_ str) -> Option<ErrorLog<'_>> {
         loc = None;
         hint = Some(first_par);
     }
-    Some(ErrorLog {
+    Ok(ErrorLog {
         msg,
         hint,
         file,
@@ -75,14 +79,20 @@ fn main() {
     let inputs = [
         "ERROR: unflagazed plungus (/file/location at line 42, column 67) (plungus was flagazed line 2)",
         "ERROR: fluxmoxxed (very bad!)",
+        "ERROR: overflunked (see foo(bar))",
         "ERROR: bazoombled (/etc/baz at line 0, column 6)",
+        "ERROR: misplumbed (/etc/baz at line 0)",
         "Not an error message",
     ];
 
     for input in inputs {
         println!("{input}");
-        let value = parse(input);
-        dbg!(value);
+        match parse(input) {
+            Ok(value) => {
+                dbg!(value);
+            }
+            Err(err) => println!("{err}"),
+        }
         println!("------")
     }
 }
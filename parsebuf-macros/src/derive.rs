@@ -0,0 +1,346 @@
+use std::fmt::Write;
+
+use proc_macro::{Delimiter, Span, TokenStream, TokenTree};
+
+use crate::{Error, string_value};
+
+#[derive(Default)]
+struct FieldAttrs {
+    prefix: Option<String>,
+    terminator: Option<String>,
+    suffix: Option<String>,
+    or_end: bool,
+    optional: bool,
+}
+
+struct Field {
+    name: String,
+    ty: Vec<TokenTree>,
+    attrs: FieldAttrs,
+    span: Span,
+}
+
+fn is_punct(tt: &TokenTree, c: char) -> bool {
+    matches!(tt, TokenTree::Punct(p) if p.as_char() == c)
+}
+
+fn is_ident(tt: &TokenTree, name: &str) -> bool {
+    matches!(tt, TokenTree::Ident(i) if i.to_string() == name)
+}
+
+fn stringify(tokens: &[TokenTree]) -> String {
+    tokens.iter().cloned().collect::<TokenStream>().to_string()
+}
+
+// Splits on commas outside of `<...>`, which are not token groups.
+fn split_top_level(tokens: impl IntoIterator<Item = TokenTree>) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![Vec::new()];
+    let mut depth = 0usize;
+    let mut prev_dash = false;
+    for tt in tokens {
+        match &tt {
+            TokenTree::Punct(p) if p.as_char() == ',' && depth == 0 => {
+                parts.push(Vec::new());
+                prev_dash = false;
+                continue;
+            }
+            TokenTree::Punct(p) if p.as_char() == '<' => depth += 1,
+            // The `>` of `->` does not close anything.
+            TokenTree::Punct(p) if p.as_char() == '>' && !prev_dash => {
+                depth = depth.saturating_sub(1)
+            }
+            _ => {}
+        }
+        prev_dash = is_punct(&tt, '-');
+        parts.last_mut().unwrap().push(tt);
+    }
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+fn parse_attrs(args: TokenStream, attrs: &mut FieldAttrs) -> Result<(), Error> {
+    for arg in split_top_level(args) {
+        let TokenTree::Ident(key) = &arg[0] else {
+            return Err(Error::new(arg[0].span(), "expected a `parse` option"));
+        };
+        let value = match &arg[1..] {
+            [] => None,
+            [eq, TokenTree::Literal(literal)] if is_punct(eq, '=') => Some(
+                string_value(literal)
+                    .ok_or_else(|| Error::new(literal.span(), "expected a string literal"))?,
+            ),
+            rest => return Err(Error::new(rest[0].span(), "expected `= \"...\"`")),
+        };
+        let key_name = key.to_string();
+        match (key_name.as_str(), value) {
+            ("prefix", Some(value)) => attrs.prefix = Some(value),
+            ("terminator", Some(value)) => attrs.terminator = Some(value),
+            ("suffix", Some(value)) => attrs.suffix = Some(value),
+            ("or_end", None) => attrs.or_end = true,
+            ("optional", None) => attrs.optional = true,
+            ("prefix" | "terminator" | "suffix", None) => {
+                return Err(Error::new(
+                    key.span(),
+                    format!("`{key_name}` needs a string value"),
+                ));
+            }
+            ("or_end" | "optional", Some(_)) => {
+                return Err(Error::new(
+                    key.span(),
+                    format!("`{key_name}` takes no value"),
+                ));
+            }
+            _ => {
+                return Err(Error::new(
+                    key.span(),
+                    format!(
+                        "unknown `parse` option `{key_name}`, expected one of prefix, terminator, suffix, or_end, optional"
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn parse_field(tokens: Vec<TokenTree>) -> Result<Field, Error> {
+    let mut attrs = FieldAttrs::default();
+    let mut tokens = tokens.into_iter().peekable();
+    while tokens.peek().is_some_and(|tt| is_punct(tt, '#')) {
+        tokens.next();
+        let Some(TokenTree::Group(attr)) = tokens.next() else {
+            unreachable!("attributes are bracketed");
+        };
+        let mut inner = attr.stream().into_iter();
+        if let (Some(path), Some(TokenTree::Group(args)), None) =
+            (inner.next(), inner.next(), inner.next())
+            && is_ident(&path, "parse")
+            && args.delimiter() == Delimiter::Parenthesis
+        {
+            parse_attrs(args.stream(), &mut attrs)?;
+        }
+    }
+    if tokens.peek().is_some_and(|tt| is_ident(tt, "pub")) {
+        tokens.next();
+        if matches!(tokens.peek(), Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis)
+        {
+            tokens.next();
+        }
+    }
+    let Some(TokenTree::Ident(name)) = tokens.next() else {
+        unreachable!("named fields start with their name");
+    };
+    tokens.next();
+    Ok(Field {
+        name: name.to_string(),
+        ty: tokens.collect(),
+        attrs,
+        span: name.span(),
+    })
+}
+
+struct Generics {
+    params: Vec<Vec<TokenTree>>,
+    where_clause: Vec<TokenTree>,
+}
+
+impl Generics {
+    fn lifetime(&self) -> Option<String> {
+        self.params
+            .iter()
+            .find(|param| is_punct(&param[0], '\''))
+            .map(|param| stringify(&param[..2]).replace(' ', ""))
+    }
+
+    fn names(&self) -> String {
+        let names: Vec<String> = self
+            .params
+            .iter()
+            .map(|param| match &param[..] {
+                [quote, lifetime, ..] if is_punct(quote, '\'') => format!("'{lifetime}"),
+                [konst, name, ..] if is_ident(konst, "const") => name.to_string(),
+                [name, ..] => name.to_string(),
+                [] => unreachable!("empty parts are dropped"),
+            })
+            .collect();
+        names.join(", ")
+    }
+}
+
+// The `Option<...>` argument of an optional field's type.
+fn option_inner(ty: &[TokenTree]) -> Option<&[TokenTree]> {
+    let start = match ty {
+        [option, open, ..] if is_ident(option, "Option") && is_punct(open, '<') => 2,
+        _ => {
+            let option = ty.iter().position(|tt| is_ident(tt, "Option"))?;
+            let is_path = ty[..option]
+                .iter()
+                .all(|tt| is_punct(tt, ':') || matches!(tt, TokenTree::Ident(_)));
+            if !is_path || !ty.get(option + 1).is_some_and(|tt| is_punct(tt, '<')) {
+                return None;
+            }
+            option + 2
+        }
+    };
+    ty.last()
+        .filter(|tt| is_punct(tt, '>'))
+        .map(|_| &ty[start..ty.len() - 1])
+}
+
+fn gen_field(field: &Field, code: &mut String) -> Result<(), Error> {
+    let attrs = &field.attrs;
+    let ty = if attrs.optional {
+        option_inner(&field.ty).ok_or_else(|| {
+            Error::new(
+                field.span,
+                "`optional` fields must have an `Option<...>` type",
+            )
+        })?
+    } else {
+        &field.ty[..]
+    };
+    let is_text = ty.first().is_some_and(|tt| is_punct(tt, '&'));
+    let ty = stringify(ty);
+    let value = match (is_text, &attrs.terminator) {
+        (true, _) => "|c| ::core::result::Result::Ok(::parsebuf::__private::text(c))".to_owned(),
+        (false, Some(_)) => format!("|c| ::parsebuf::__private::value::<{ty}>(c)"),
+        (false, None) => format!("|c| c.take::<{ty}>()"),
+    };
+    let quoted = |s: &Option<String>| match s {
+        Some(s) => format!("::core::option::Option::Some({s:?})"),
+        None => "::core::option::Option::None".to_owned(),
+    };
+    // Text without a terminator runs to the end of the data.
+    let or_end = attrs.or_end || (is_text && attrs.terminator.is_none());
+    let call = format!(
+        "::parsebuf::__private::field(c, &::parsebuf::__private::FieldSpec {{ \
+         name: {name:?}, prefix: {prefix}, terminator: {terminator}, or_end: {or_end}, suffix: {suffix} }}, {value})",
+        name = field.name,
+        prefix = quoted(&attrs.prefix),
+        terminator = quoted(&attrs.terminator),
+        suffix = quoted(&attrs.suffix),
+    );
+    if attrs.optional {
+        writeln!(
+            code,
+            "let {} = __parsebuf_c.step_value(|c| {call}).ok();",
+            field.name
+        )
+        .unwrap();
+    } else {
+        writeln!(
+            code,
+            "let {} = {{ let c = &mut *__parsebuf_c; {call}? }};",
+            field.name
+        )
+        .unwrap();
+    }
+    Ok(())
+}
+
+pub(crate) fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut tokens = input.into_iter().peekable();
+    // Outer attributes and visibility.
+    while let Some(tt) = tokens.peek() {
+        if is_ident(tt, "struct") {
+            break;
+        }
+        if is_ident(tt, "enum") || is_ident(tt, "union") {
+            return Err(Error::new(
+                tt.span(),
+                "`Parse` can only be derived for structs",
+            ));
+        }
+        tokens.next();
+    }
+    tokens.next();
+    let Some(TokenTree::Ident(name)) = tokens.next() else {
+        return Err(Error::new(Span::call_site(), "expected a struct name"));
+    };
+
+    let mut generics = Generics {
+        params: Vec::new(),
+        where_clause: Vec::new(),
+    };
+    if tokens.peek().is_some_and(|tt| is_punct(tt, '<')) {
+        tokens.next();
+        let mut depth = 1;
+        let mut prev_dash = false;
+        let mut params = Vec::new();
+        for tt in tokens.by_ref() {
+            if is_punct(&tt, '<') {
+                depth += 1;
+            } else if is_punct(&tt, '>') && !prev_dash {
+                depth -= 1;
+                if depth == 0 {
+                    break;
+                }
+            }
+            prev_dash = is_punct(&tt, '-');
+            params.push(tt);
+        }
+        generics.params = split_top_level(params);
+    }
+    let body = loop {
+        match tokens.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => break group,
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Parenthesis => {
+                return Err(Error::new(
+                    group.span(),
+                    "`Parse` can only be derived for structs with named fields",
+                ));
+            }
+            Some(tt) => generics.where_clause.push(tt),
+            None => {
+                return Err(Error::new(
+                    name.span(),
+                    "`Parse` can only be derived for structs with named fields",
+                ));
+            }
+        }
+    };
+
+    let fields = split_top_level(body.stream())
+        .into_iter()
+        .map(parse_field)
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut code = String::new();
+    for field in &fields {
+        gen_field(field, &mut code)?;
+    }
+    let field_names: Vec<&str> = fields.iter().map(|f| f.name.as_str()).collect();
+
+    // Defaults are not allowed on impl parameters.
+    let mut impl_params: Vec<String> = generics
+        .params
+        .iter()
+        .map(|param| {
+            let end = param
+                .iter()
+                .position(|tt| is_punct(tt, '='))
+                .unwrap_or(param.len());
+            stringify(&param[..end])
+        })
+        .collect();
+    let lifetime = generics.lifetime().unwrap_or_else(|| {
+        impl_params.insert(0, "'__parsebuf_a".to_owned());
+        "'__parsebuf_a".to_owned()
+    });
+    let expanded = format!(
+        "impl<{impl_params}> ::parsebuf::Extract<{lifetime}> for {name}<{type_params}> {where_clause} {{
+            fn extract(
+                __parsebuf_c: &mut ::parsebuf::ParseCursor<{lifetime}>,
+            ) -> ::core::result::Result<Self, ::parsebuf::CursorError> {{
+                {code}
+                ::core::result::Result::Ok(Self {{ {fields} }})
+            }}
+        }}",
+        impl_params = impl_params.join(", "),
+        type_params = generics.names(),
+        where_clause = stringify(&generics.where_clause),
+        fields = field_names.join(", "),
+    );
+    expanded
+        .parse()
+        .map_err(|_| Error::new(name.span(), "could not derive `Parse` for this struct"))
+}
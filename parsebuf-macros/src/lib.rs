@@ -0,0 +1,101 @@
+use proc_macro::{Delimiter, Group, Ident, Literal, Punct, Spacing, Span, TokenStream, TokenTree};
+
+mod derive;
+mod template;
+
+// Expands `template!(cursor, "ERROR: {msg} (at line {line:u64})")` into moves
+// of `cursor` returning `Result<(msg, line), CursorError>`. Append
+// `=> Path` to build the struct `Path { msg, line }` instead of a tuple.
+// `[...]` marks an optional group whose holes become `Option`s, `{_}` skips
+// text, and doubling any of `{}[]` gives the literal character.
+#[proc_macro]
+pub fn template(input: TokenStream) -> TokenStream {
+    template::expand(input).unwrap_or_else(Error::into_compile_error)
+}
+
+// Implements `Extract` for a struct with named fields by reading them in
+// order. Each field can be tuned with `#[parse(...)]`:
+// - `prefix = "..."` must come right before the field and is skipped;
+// - `terminator = "..."` ends the field at its first occurrence, which is
+//   left for the next field, and `or_end` falls back to the end of the data
+//   when it is missing;
+// - `suffix = "..."` must come right after the field and is skipped;
+// - `optional` makes a failure to read the whole field leave it `None`.
+// `&str` fields take the text up to the terminator, or the rest of the
+// data. Other fields are parsed with `FromStr` when they have a terminator
+// and taken with `Extract` otherwise, so derived structs nest.
+#[proc_macro_derive(Parse, attributes(parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    derive::expand(input).unwrap_or_else(Error::into_compile_error)
+}
+
+struct Error {
+    span: Span,
+    message: String,
+}
+
+impl Error {
+    fn new(span: Span, message: impl Into<String>) -> Self {
+        Self {
+            span,
+            message: message.into(),
+        }
+    }
+
+    fn into_compile_error(self) -> TokenStream {
+        let mut message = Literal::string(&self.message);
+        message.set_span(self.span);
+        let mut bang = Punct::new('!', Spacing::Alone);
+        bang.set_span(self.span);
+        let mut args = Group::new(Delimiter::Parenthesis, TokenTree::Literal(message).into());
+        args.set_span(self.span);
+        TokenStream::from_iter([
+            TokenTree::Ident(Ident::new("compile_error", self.span)),
+            TokenTree::Punct(bang),
+            TokenTree::Group(args),
+        ])
+    }
+}
+
+// The value of a plain or raw string literal, `None` for any other token.
+fn string_value(literal: &Literal) -> Option<String> {
+    let repr = literal.to_string();
+    if let Some(raw) = repr.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let quoted = &raw[hashes..raw.len() - hashes];
+        return Some(quoted.strip_prefix('"')?.strip_suffix('"')?.to_owned());
+    }
+    let quoted = repr.strip_prefix('"')?.strip_suffix('"')?;
+    let mut value = String::new();
+    let mut chars = quoted.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            'x' => {
+                let code = u8::from_str_radix(chars.as_str().get(..2)?, 16).ok()?;
+                chars.nth(1);
+                value.push(char::from(code));
+            }
+            'u' => {
+                let rest = chars.as_str().strip_prefix('{')?;
+                let close = rest.find('}')?;
+                let code = u32::from_str_radix(&rest[..close].replace('_', ""), 16).ok()?;
+                value.push(char::from_u32(code)?);
+                chars.nth(close + 1);
+            }
+            '\n' => {
+                let rest = chars.as_str().trim_start();
+                chars = rest.chars();
+            }
+            c => value.push(c),
+        }
+    }
+    Some(value)
+}
@@ -0,0 +1,348 @@
+use std::fmt::Write;
+use std::iter::Peekable;
+use std::str::Chars;
+
+use proc_macro::{Delimiter, Group, Span, TokenStream, TokenTree};
+
+use crate::{Error, string_value};
+
+pub(crate) enum Node {
+    Literal(String),
+    Hole { name: String, ty: Option<String> },
+    Group(Vec<Node>),
+}
+
+pub(crate) fn parse(template: &str) -> Result<Vec<Node>, String> {
+    parse_seq(&mut template.chars().peekable(), false)
+}
+
+fn parse_seq(chars: &mut Peekable<Chars<'_>>, nested: bool) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new();
+    let mut literal = String::new();
+    let flush = |literal: &mut String, nodes: &mut Vec<Node>| {
+        if !literal.is_empty() {
+            nodes.push(Node::Literal(std::mem::take(literal)));
+        }
+    };
+    loop {
+        match chars.next() {
+            None if nested => return Err("unclosed `[` in template".to_owned()),
+            None => break,
+            Some(c @ ('{' | '}' | '[' | ']')) if chars.peek() == Some(&c) => {
+                chars.next();
+                literal.push(c);
+            }
+            Some('{') => {
+                flush(&mut literal, &mut nodes);
+                let mut hole = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => hole.push(c),
+                        None => return Err("unclosed `{` in template".to_owned()),
+                    }
+                }
+                nodes.push(parse_hole(&hole)?);
+            }
+            Some('[') => {
+                flush(&mut literal, &mut nodes);
+                let group = parse_seq(chars, true)?;
+                if group.is_empty() {
+                    return Err("empty optional group in template".to_owned());
+                }
+                nodes.push(Node::Group(group));
+            }
+            Some(']') if nested => break,
+            Some(c @ ('}' | ']')) => {
+                return Err(format!(
+                    "unmatched `{c}` in template, write `{c}{c}` for a literal one"
+                ));
+            }
+            Some(c) => literal.push(c),
+        }
+    }
+    flush(&mut literal, &mut nodes);
+    Ok(nodes)
+}
+
+fn parse_hole(hole: &str) -> Result<Node, String> {
+    let (name, ty) = match hole.split_once(':') {
+        Some((name, ty)) => (name.trim(), Some(ty.trim())),
+        None => (hole.trim(), None),
+    };
+    let is_ident = name
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !is_ident {
+        return Err(format!("`{{{hole}}}` is not a valid hole name"));
+    }
+    if ty == Some("") {
+        return Err(format!("missing type after `:` in `{{{hole}}}`"));
+    }
+    Ok(Node::Hole {
+        name: name.to_owned(),
+        ty: ty.map(str::to_owned),
+    })
+}
+
+// Pushes the literals that can come right after `seqs`, innermost first, and
+// returns whether the end of the template can be reached without any.
+fn follow(seqs: &[&[Node]], literals: &mut Vec<String>) -> Result<bool, String> {
+    for seq in seqs {
+        if !first(seq, literals)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn first(seq: &[Node], literals: &mut Vec<String>) -> Result<bool, String> {
+    for node in seq {
+        match node {
+            Node::Literal(literal) => {
+                literals.push(literal.clone());
+                return Ok(false);
+            }
+            Node::Hole { name, .. } => {
+                return Err(format!(
+                    "hole `{name}` directly follows another hole, separate them with a literal"
+                ));
+            }
+            // Groups are optional, so whatever is after them may come first too.
+            Node::Group(group) => {
+                first(group, literals)?;
+            }
+        }
+    }
+    Ok(true)
+}
+
+// A bound hole, and whether it is already an `Option` because it sits in a
+// nested group.
+struct Binding {
+    name: String,
+    optional: bool,
+}
+
+fn gen_seq(
+    nodes: &[Node],
+    outer: &[&[Node]],
+    code: &mut String,
+    bindings: &mut Vec<Binding>,
+) -> Result<(), String> {
+    for (i, node) in nodes.iter().enumerate() {
+        let rest: Vec<&[Node]> = std::iter::once(&nodes[i + 1..])
+            .chain(outer.iter().copied())
+            .collect();
+        match node {
+            Node::Literal(literal) => {
+                writeln!(
+                    code,
+                    "::parsebuf::__private::literal(__parsebuf_c, {literal:?})?;"
+                )
+                .unwrap();
+            }
+            Node::Hole { name, ty } => {
+                let mut literals = Vec::new();
+                let to_end = follow(&rest, &mut literals)?;
+                writeln!(
+                    code,
+                    "::parsebuf::__private::hole(__parsebuf_c, {name:?}, &{literals:?}, {to_end})?;"
+                )
+                .unwrap();
+                match (name.as_str(), ty) {
+                    ("_", _) => writeln!(code, "__parsebuf_c.back_to_front();").unwrap(),
+                    (_, Some(ty)) => writeln!(
+                        code,
+                        "let {name}: {ty} = ::parsebuf::__private::parse(__parsebuf_c, {name:?})?;"
+                    )
+                    .unwrap(),
+                    (_, None) => writeln!(
+                        code,
+                        "let {name} = ::parsebuf::__private::text(__parsebuf_c);"
+                    )
+                    .unwrap(),
+                }
+                if name != "_" {
+                    bindings.push(Binding {
+                        name: name.clone(),
+                        optional: false,
+                    });
+                }
+            }
+            Node::Group(group) => {
+                let mut inner_code = String::new();
+                let mut inner = Vec::new();
+                gen_seq(group, &rest, &mut inner_code, &mut inner)?;
+                let names: String = inner.iter().map(|b| format!("{}, ", b.name)).collect();
+                let values: String = inner
+                    .iter()
+                    .map(|b| format!("__parsebuf_{}, ", b.name))
+                    .collect();
+                for binding in &inner {
+                    writeln!(
+                        code,
+                        "let mut {} = ::core::option::Option::None;",
+                        binding.name
+                    )
+                    .unwrap();
+                }
+                writeln!(
+                    code,
+                    "if let ::core::result::Result::Ok(({values})) = __parsebuf_c.step_value(|__parsebuf_c| {{\n\
+                     {inner_code}::core::result::Result::Ok(({names}))\n}}) {{"
+                )
+                .unwrap();
+                for binding in &inner {
+                    let name = &binding.name;
+                    if binding.optional {
+                        writeln!(code, "{name} = __parsebuf_{name};").unwrap();
+                    } else {
+                        writeln!(
+                            code,
+                            "{name} = ::core::option::Option::Some(__parsebuf_{name});"
+                        )
+                        .unwrap();
+                    }
+                }
+                writeln!(code, "}}").unwrap();
+                bindings.extend(inner.into_iter().map(|b| Binding {
+                    name: b.name,
+                    optional: true,
+                }));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let mut tokens = input.into_iter().peekable();
+    let cursor: TokenStream = tokens
+        .by_ref()
+        .take_while(|tt| !matches!(tt, TokenTree::Punct(p) if p.as_char() == ','))
+        .collect();
+    if cursor.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "expected a cursor followed by a template string",
+        ));
+    }
+    let (template, span) = match tokens.next() {
+        Some(TokenTree::Literal(literal)) => (string_value(&literal), literal.span()),
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::None => {
+            match group.stream().into_iter().next() {
+                Some(TokenTree::Literal(literal)) => (string_value(&literal), literal.span()),
+                _ => (None, group.span()),
+            }
+        }
+        Some(other) => (None, other.span()),
+        None => (None, Span::call_site()),
+    };
+    let template = template.ok_or_else(|| Error::new(span, "expected a template string"))?;
+    let nodes = parse(&template).map_err(|message| Error::new(span, message))?;
+
+    let output: Option<TokenStream> = match tokens.next() {
+        None => None,
+        Some(TokenTree::Punct(p)) if p.as_char() == ',' && tokens.peek().is_none() => None,
+        Some(TokenTree::Punct(p)) if p.as_char() == '=' => match tokens.next() {
+            Some(TokenTree::Punct(p)) if p.as_char() == '>' => Some(tokens.collect()),
+            _ => return Err(Error::new(p.span(), "expected `=> StructPath`")),
+        },
+        Some(other) => return Err(Error::new(other.span(), "expected `=> StructPath`")),
+    };
+
+    let mut code = String::from("__parsebuf_c.back_to_front();\n");
+    let mut bindings = Vec::new();
+    gen_seq(&nodes, &[], &mut code, &mut bindings).map_err(|message| Error::new(span, message))?;
+    for (i, binding) in bindings.iter().enumerate() {
+        if bindings[..i].iter().any(|b| b.name == binding.name) {
+            return Err(Error::new(
+                span,
+                format!("hole `{}` appears twice in template", binding.name),
+            ));
+        }
+    }
+    let fields: String = bindings.iter().map(|b| format!("{}, ", b.name)).collect();
+    let value = match output {
+        Some(path) => {
+            let mut value = path;
+            value.extend([TokenTree::Group(Group::new(
+                Delimiter::Brace,
+                fields.parse().unwrap(),
+            ))]);
+            value
+        }
+        None => format!("({fields})").parse().unwrap(),
+    };
+    // Only the types written in holes can fail to parse.
+    let mut body: TokenStream = code
+        .parse()
+        .map_err(|_| Error::new(span, "invalid type in template hole"))?;
+    body.extend("::core::result::Result::Ok".parse::<TokenStream>().unwrap());
+    body.extend([TokenTree::Group(Group::new(Delimiter::Parenthesis, value))]);
+
+    let mut closure: TokenStream = "|__parsebuf_c|".parse().unwrap();
+    closure.extend([TokenTree::Group(Group::new(Delimiter::Brace, body))]);
+    let mut expanded =
+        TokenStream::from(TokenTree::Group(Group::new(Delimiter::Parenthesis, cursor)));
+    expanded.extend(".step_value".parse::<TokenStream>().unwrap());
+    expanded.extend([TokenTree::Group(Group::new(
+        Delimiter::Parenthesis,
+        closure,
+    ))]);
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(template: &str) -> Result<(), String> {
+        let nodes = parse(template)?;
+        gen_seq(&nodes, &[], &mut String::new(), &mut Vec::new())
+    }
+
+    #[test]
+    fn test_parse_nested_groups_and_escapes() {
+        let nodes = parse("a{{[{b:u8}]]]c]]").unwrap();
+        let [Node::Literal(a), Node::Group(group), Node::Literal(c)] = &nodes[..] else {
+            panic!("unexpected shape");
+        };
+        assert_eq!((a.as_str(), c.as_str()), ("a{", "c]"));
+        assert!(matches!(
+            &group[..],
+            [Node::Hole { name, ty: Some(ty) }, Node::Literal(close)]
+                if name == "b" && ty == "u8" && close == "]"
+        ));
+    }
+
+    #[test]
+    fn test_template_errors() {
+        assert!(check("{a} {b}").is_ok());
+        assert_eq!(check("[{a}").unwrap_err(), "unclosed `[` in template");
+        assert_eq!(check("{a").unwrap_err(), "unclosed `{` in template");
+        assert_eq!(
+            check("x[]").unwrap_err(),
+            "empty optional group in template"
+        );
+        assert_eq!(
+            check("a}").unwrap_err(),
+            "unmatched `}` in template, write `}}` for a literal one"
+        );
+        assert_eq!(
+            check("{1x}").unwrap_err(),
+            "`{1x}` is not a valid hole name"
+        );
+        assert_eq!(
+            check("{a:}").unwrap_err(),
+            "missing type after `:` in `{a:}`"
+        );
+        assert_eq!(
+            check("{a}[{b}]").unwrap_err(),
+            "hole `b` directly follows another hole, separate them with a literal"
+        );
+    }
+}
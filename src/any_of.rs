@@ -0,0 +1,341 @@
+use std::collections::VecDeque;
+use std::ops::Range;
+
+use crate::pattern::describe_literal;
+use crate::{Haystack, Pattern};
+
+// Matches any of several literals in a single pass, the leftmost match
+// winning and, among those starting at the same place, the literal listed
+// first, so list a literal before the shorter ones it starts or ends with.
+// Pass it as `&mut` to read `matched` after the move.
+#[derive(Clone, Debug)]
+pub struct AnyOf<L> {
+    literals: Vec<L>,
+    forward: Automaton,
+    // Built from the reversed literals, for searching from the end.
+    backward: Automaton,
+    matched: Option<usize>,
+}
+
+// An Aho-Corasick automaton: a trie of the literals where each state also
+// links to the state of its longest proper suffix that is in the trie.
+#[derive(Clone, Debug)]
+struct Automaton {
+    children: Vec<Vec<(u8, usize)>>,
+    fail: Vec<usize>,
+    depth: Vec<usize>,
+    // Literals ending at each state, including those of its suffixes.
+    outputs: Vec<Vec<usize>>,
+    lens: Vec<usize>,
+}
+
+impl Automaton {
+    fn new(literals: &[Vec<u8>]) -> Self {
+        let mut children: Vec<Vec<(u8, usize)>> = vec![Vec::new()];
+        let mut depth = vec![0];
+        let mut outputs = vec![Vec::new()];
+        for (index, literal) in literals.iter().enumerate() {
+            let mut state = 0;
+            for &byte in literal {
+                state = match children[state].iter().find(|&&(b, _)| b == byte) {
+                    Some(&(_, child)) => child,
+                    None => {
+                        let child = children.len();
+                        children[state].push((byte, child));
+                        children.push(Vec::new());
+                        depth.push(depth[state] + 1);
+                        outputs.push(Vec::new());
+                        child
+                    }
+                };
+            }
+            outputs[state].push(index);
+        }
+        let mut automaton = Self {
+            fail: vec![0; children.len()],
+            children,
+            depth,
+            outputs,
+            lens: literals.iter().map(Vec::len).collect(),
+        };
+        // Breadth first, so that the links of shallower states are complete.
+        let mut queue: VecDeque<usize> = automaton.children[0].iter().map(|&(_, c)| c).collect();
+        while let Some(state) = queue.pop_front() {
+            let inherited = automaton.outputs[automaton.fail[state]].clone();
+            automaton.outputs[state].extend(inherited);
+            for (byte, child) in automaton.children[state].clone() {
+                automaton.fail[child] = automaton.next(automaton.fail[state], byte);
+                queue.push_back(child);
+            }
+        }
+        automaton
+    }
+
+    fn next(&self, mut state: usize, byte: u8) -> usize {
+        loop {
+            if let Some(&(_, child)) = self.children[state].iter().find(|&&(b, _)| b == byte) {
+                return child;
+            }
+            if state == 0 {
+                return 0;
+            }
+            state = self.fail[state];
+        }
+    }
+
+    // The leftmost match in `bytes` accepted by `valid`, the lowest literal
+    // index on a tie, with the index.
+    fn leftmost(
+        &self,
+        bytes: impl Iterator<Item = u8>,
+        mut valid: impl FnMut(&Range<usize>) -> bool,
+    ) -> Option<(usize, Range<usize>)> {
+        let mut best: Option<(usize, Range<usize>)> = None;
+        let mut state = 0;
+        let mut pos = 0;
+        let mut bytes = bytes.fuse();
+        loop {
+            for &index in &self.outputs[state] {
+                let found = pos - self.lens[index]..pos;
+                let better = best.as_ref().is_none_or(|(best_index, best_range)| {
+                    (found.start, index) < (best_range.start, *best_index)
+                });
+                if better && valid(&found) {
+                    best = Some((index, found));
+                }
+            }
+            // Matches still in progress started `depth` bytes ago at most.
+            if let Some((_, found)) = &best
+                && pos - self.depth[state] > found.start
+            {
+                break;
+            }
+            let Some(byte) = bytes.next() else {
+                break;
+            };
+            state = self.next(state, byte);
+            pos += 1;
+        }
+        best
+    }
+}
+
+impl<L: AsRef<[u8]>> AnyOf<L> {
+    pub fn new(literals: impl IntoIterator<Item = L>) -> Self {
+        let literals: Vec<L> = literals.into_iter().collect();
+        let forward: Vec<Vec<u8>> = literals.iter().map(|l| l.as_ref().to_vec()).collect();
+        let backward: Vec<Vec<u8>> = forward
+            .iter()
+            .map(|l| l.iter().rev().copied().collect())
+            .collect();
+        Self {
+            forward: Automaton::new(&forward),
+            backward: Automaton::new(&backward),
+            literals,
+            matched: None,
+        }
+    }
+}
+
+impl<L> AnyOf<L> {
+    // Index of the literal of the last search, which for the `*Many`
+    // locations is the one matched last, or `None` if it failed.
+    pub fn matched(&self) -> Option<usize> {
+        self.matched
+    }
+
+    fn record(&mut self, found: Option<(usize, Range<usize>)>) -> Option<Range<usize>> {
+        self.matched = found.as_ref().map(|(index, _)| *index);
+        found.map(|(_, range)| range)
+    }
+}
+
+impl<L: AsRef<[u8]>> AnyOf<L> {
+    fn starting_at(&self, bytes: &[u8]) -> Option<(usize, Range<usize>)> {
+        self.literals
+            .iter()
+            .position(|literal| bytes.starts_with(literal.as_ref()))
+            .map(|index| (index, 0..self.literals[index].as_ref().len()))
+    }
+
+    fn ending_at(&self, bytes: &[u8]) -> Option<(usize, Range<usize>)> {
+        self.literals
+            .iter()
+            .position(|literal| bytes.ends_with(literal.as_ref()))
+            .map(|index| {
+                (
+                    index,
+                    bytes.len() - self.literals[index].as_ref().len()..bytes.len(),
+                )
+            })
+    }
+}
+
+impl<H, L> Pattern<H> for AnyOf<L>
+where
+    H: ?Sized + Haystack,
+    L: AsRef<H> + AsRef<[u8]>,
+{
+    fn find_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        let bytes = haystack.as_bytes();
+        let found = self.forward.leftmost(bytes.iter().copied(), |found| {
+            haystack.is_boundary(found.start) && haystack.is_boundary(found.end)
+        });
+        self.record(found)
+    }
+
+    fn rfind_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        let bytes = haystack.as_bytes();
+        let len = bytes.len();
+        let found = self
+            .backward
+            .leftmost(bytes.iter().rev().copied(), |found| {
+                haystack.is_boundary(len - found.start) && haystack.is_boundary(len - found.end)
+            })
+            .map(|(index, found)| (index, len - found.end..len - found.start));
+        self.record(found)
+    }
+
+    fn prefix_len(&mut self, haystack: &H) -> Option<usize> {
+        let found = self.starting_at(haystack.as_bytes());
+        self.record(found).map(|range| range.end)
+    }
+
+    fn suffix_len(&mut self, haystack: &H) -> Option<usize> {
+        let found = self.ending_at(haystack.as_bytes());
+        self.record(found).map(|range| range.len())
+    }
+
+    fn describe(&self) -> String {
+        let alternatives: Vec<String> = self
+            .literals
+            .iter()
+            .map(|literal| describe_literal(literal.as_ref()))
+            .collect();
+        format!("one of [{}]", alternatives.join(", "))
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        self.literals
+            .iter()
+            .map(|literal| AsRef::<[u8]>::as_ref(literal).len())
+            .max()
+    }
+}
+
+impl<H, L> Pattern<H> for &mut AnyOf<L>
+where
+    H: ?Sized + Haystack,
+    L: AsRef<H> + AsRef<[u8]>,
+{
+    fn find_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        (**self).find_in(haystack)
+    }
+
+    fn rfind_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        (**self).rfind_in(haystack)
+    }
+
+    fn prefix_len(&mut self, haystack: &H) -> Option<usize> {
+        (**self).prefix_len(haystack)
+    }
+
+    fn suffix_len(&mut self, haystack: &H) -> Option<usize> {
+        (**self).suffix_len(haystack)
+    }
+
+    fn describe(&self) -> String {
+        Pattern::<H>::describe(&**self)
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Pattern::<H>::max_len(&**self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteCursor, InwardStrategy, ParseCursor, PatternLoc};
+
+    #[test]
+    fn test_reports_matched_literal() {
+        let data = "noise WARN: disk\nFATAL: gone\nERROR: late";
+        let mut levels = AnyOf::new(["ERROR:", "WARN:", "FATAL:"]);
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward(&mut levels, PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(levels.matched(), Some(1));
+        assert_eq!(cursor.front_rem(), "WARN: disk\nFATAL: gone\nERROR: late");
+
+        let mut cursor = ParseCursor::new_empty_end(data);
+        cursor
+            .front_backward(
+                &mut levels,
+                PatternLoc::FirstIncluded,
+                InwardStrategy::WholeData,
+            )
+            .unwrap();
+        assert_eq!(levels.matched(), Some(0));
+        assert_eq!(cursor.front_rem(), "ERROR: late");
+        cursor
+            .front_backward(
+                &mut levels,
+                PatternLoc::EndOfLast,
+                InwardStrategy::WholeData,
+            )
+            .unwrap();
+        assert_eq!(levels.matched(), Some(1));
+
+        let err = ParseCursor::new_empty_start("INFO: fine")
+            .front_forward(&mut levels, PatternLoc::FirstExcluded)
+            .unwrap_err();
+        assert_eq!(err.pattern(), "one of ['ERROR:', 'WARN:', 'FATAL:']");
+        assert_eq!(levels.matched(), None);
+    }
+
+    #[test]
+    fn test_order_and_repeats() {
+        let mut ops = AnyOf::new(["==", "=", "!="]);
+        let mut cursor = ParseCursor::new_empty_start("a == b");
+        cursor
+            .front_forward(&mut ops, PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!((cursor.cursor(), ops.matched()), ("a ==", Some(0)));
+
+        let mut cursor = ParseCursor::new_empty_start("!====x");
+        cursor
+            .front_forward(&mut ops, PatternLoc::BeginningMany)
+            .unwrap();
+        assert_eq!((cursor.cursor(), ops.matched()), ("!====", Some(1)));
+        let mut cursor = ParseCursor::new_empty_start("x=!=");
+        cursor
+            .front_forward(&mut ops, PatternLoc::StartOfSuffixMany)
+            .unwrap();
+        assert_eq!((cursor.front_rem(), ops.matched()), ("=", Some(1)));
+
+        // Partial matches of one literal fall back to the others.
+        let mut words = AnyOf::new(["abce", "bcd", "cdx", "c"]);
+        assert_eq!(words.find_in("abcx"), Some(2..3));
+        assert_eq!(words.matched(), Some(3));
+        assert_eq!(words.find_in("abcdx"), Some(1..4));
+        assert_eq!(words.matched(), Some(1));
+        assert_eq!(words.rfind_in("abcdx"), Some(2..5));
+        assert_eq!(words.matched(), Some(2));
+        assert_eq!(words.find_in("abx"), None);
+        assert_eq!(words.matched(), None);
+
+        let mut cursor = ByteCursor::new_empty_end(b"\xff\r\nline\n");
+        let mut newlines = AnyOf::new([&b"\r\n"[..], b"\n"]);
+        cursor
+            .back_backward(&mut newlines, PatternLoc::BeginningOnce)
+            .unwrap()
+            .back_backward(&mut newlines, PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(
+            (cursor.cursor(), newlines.matched()),
+            (&b"\r\nline\n"[..], Some(0))
+        );
+    }
+}
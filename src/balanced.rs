@@ -0,0 +1,200 @@
+use std::ops::Range;
+
+use crate::{Haystack, Pattern, PatternLoc};
+
+// Matches the `close` that balances an `open` already behind the search
+// start, skipping nested pairs: searching forward it is the first `close`
+// not paired with a later `open`, searching backward the last `open` not
+// paired with an earlier `close`. Only locations counted from the end being
+// moved are supported, since that end is what the delimiter balances: the
+// `Last*`, `EndOfLast`, `StartOfSuffixMany` and `NthLast*` ones count from
+// the far end of the view and fail. `BeginningOnce` only succeeds when the
+// balancing delimiter is right at the end being moved.
+#[derive(Clone, Debug)]
+pub struct Balanced<O, C> {
+    open: O,
+    close: C,
+}
+
+impl<O, C> Balanced<O, C> {
+    pub fn new(open: O, close: C) -> Self {
+        Self { open, close }
+    }
+}
+
+fn shift(range: Range<usize>, by: usize) -> Range<usize> {
+    range.start + by..range.end + by
+}
+
+impl<H, O, C> Pattern<H> for Balanced<O, C>
+where
+    H: ?Sized + Haystack,
+    O: Pattern<H>,
+    C: Pattern<H>,
+{
+    fn find_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        let mut depth = 0usize;
+        let mut pos = 0;
+        let mut next_open: Option<Range<usize>> = None;
+        loop {
+            let rest = haystack.slice(pos..haystack.len());
+            let close = shift(self.close.find_in(rest)?, pos);
+            if next_open.as_ref().is_none_or(|open| open.start < pos) {
+                next_open = self
+                    .open
+                    .find_in(rest)
+                    .filter(|open| !open.is_empty())
+                    .map(|open| shift(open, pos));
+            }
+            // On a tie the delimiter closes, so identical delimiters still pair up.
+            match next_open.take() {
+                Some(open) if open.start < close.start => {
+                    depth += 1;
+                    pos = open.end;
+                }
+                open => {
+                    next_open = open;
+                    if depth == 0 {
+                        return Some(close);
+                    }
+                    depth -= 1;
+                    pos = close.end;
+                }
+            }
+        }
+    }
+
+    fn rfind_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        let mut depth = 0usize;
+        let mut end = haystack.len();
+        let mut prev_close: Option<Range<usize>> = None;
+        loop {
+            let rest = haystack.slice(0..end);
+            let open = self.open.rfind_in(rest)?;
+            if prev_close.as_ref().is_none_or(|close| close.end > end) {
+                prev_close = self.close.rfind_in(rest).filter(|close| !close.is_empty());
+            }
+            match prev_close.take() {
+                Some(close) if close.end > open.end => {
+                    depth += 1;
+                    end = close.start;
+                }
+                close => {
+                    prev_close = close;
+                    if depth == 0 {
+                        return Some(open);
+                    }
+                    depth -= 1;
+                    end = open.start;
+                }
+            }
+        }
+    }
+
+    fn prefix_len(&mut self, haystack: &H) -> Option<usize> {
+        self.find_in(haystack)
+            .filter(|close| close.start == 0)
+            .map(|close| close.end)
+    }
+
+    fn suffix_len(&mut self, haystack: &H) -> Option<usize> {
+        self.rfind_in(haystack)
+            .filter(|open| open.end == haystack.len())
+            .map(|open| haystack.len() - open.start)
+    }
+
+    fn supports(&self, loc: PatternLoc) -> bool {
+        !matches!(
+            loc,
+            PatternLoc::LastExcluded
+                | PatternLoc::EndOfLast
+                | PatternLoc::StartOfSuffixMany
+                | PatternLoc::NthLastExcluded(_)
+                | PatternLoc::NthLastIncluded(_)
+        )
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "{} balancing {}",
+            self.close.describe(),
+            self.open.describe()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteCursor, InwardStrategy, ParseCursor};
+
+    #[test]
+    fn test_balanced_forward() {
+        let mut cursor = ParseCursor::new_empty_start("(see foo(bar)) (next)");
+        cursor
+            .back_forward('(', PatternLoc::BeginningOnce, InwardStrategy::WholeData)
+            .unwrap()
+            .front_forward(Balanced::new('(', ')'), PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "see foo(bar)");
+        cursor.front_to_back();
+        cursor
+            .front_forward(Balanced::new('(', ')'), PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "see foo(bar))");
+
+        let mut cursor = ParseCursor::new_empty_start("a (b) c");
+        let err = cursor
+            .front_forward(Balanced::new('(', ')'), PatternLoc::FirstExcluded)
+            .unwrap_err();
+        assert_eq!(err.pattern(), "')' balancing '('");
+    }
+
+    #[test]
+    fn test_balanced_backward() {
+        let data = "x (a (b) c)";
+        let mut cursor = ParseCursor::new_empty_end(data);
+        cursor
+            .front_backward(')', PatternLoc::BeginningOnce, InwardStrategy::WholeData)
+            .unwrap()
+            .back_backward(Balanced::new('(', ')'), PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "a (b) c");
+        cursor
+            .back_backward(Balanced::new('(', ')'), PatternLoc::BeginningOnce)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "(a (b) c");
+
+        let mut cursor = ParseCursor::new_empty_start("(a) b) c");
+        let err = cursor
+            .front_forward(Balanced::new('(', ')'), PatternLoc::LastExcluded)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected ')' balancing '(' (LastExcluded not supported at offset 0)"
+        );
+        cursor
+            .front_forward(Balanced::new('(', ')'), PatternLoc::NthExcluded(0))
+            .unwrap();
+        assert_eq!(cursor.cursor(), "(a) b");
+    }
+
+    #[test]
+    fn test_balanced_multi_char_delimiters_and_bytes() {
+        let mut cursor = ParseCursor::new_empty_start("{{ a {{ b }} }} rest");
+        cursor
+            .back_forward("{{", PatternLoc::BeginningOnce, InwardStrategy::WholeData)
+            .unwrap()
+            .front_forward(Balanced::new("{{", "}}"), PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), " a {{ b }} }}");
+
+        let mut cursor = ByteCursor::new_empty_start(b"[\xff[]]");
+        cursor
+            .back_forward(b'[', PatternLoc::BeginningOnce, InwardStrategy::WholeData)
+            .unwrap()
+            .front_forward(Balanced::new(b'[', b']'), PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), b"\xff[]");
+    }
+}
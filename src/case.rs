@@ -0,0 +1,241 @@
+use std::ops::Range;
+
+use crate::pattern::describe_literal;
+use crate::{Haystack, Pattern};
+
+// Matches a literal regardless of the case of its ASCII letters; other bytes
+// have to be equal.
+#[derive(Clone, Debug)]
+pub struct IgnoreAsciiCase<L> {
+    literal: L,
+}
+
+impl<L> IgnoreAsciiCase<L> {
+    pub fn new(literal: L) -> Self {
+        Self { literal }
+    }
+}
+
+impl<L: AsRef<[u8]>> IgnoreAsciiCase<L> {
+    fn matches_at(&self, bytes: &[u8], pos: usize) -> bool {
+        let needle = self.literal.as_ref();
+        bytes
+            .get(pos..pos + needle.len())
+            .is_some_and(|window| window.eq_ignore_ascii_case(needle))
+    }
+}
+
+impl<H, L> Pattern<H> for IgnoreAsciiCase<L>
+where
+    H: ?Sized + Haystack,
+    L: AsRef<H> + AsRef<[u8]>,
+{
+    fn find_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        let bytes = haystack.as_bytes();
+        let len = AsRef::<[u8]>::as_ref(&self.literal).len();
+        (0..=bytes.len().checked_sub(len)?)
+            .find(|&pos| haystack.is_boundary(pos) && self.matches_at(bytes, pos))
+            .map(|pos| pos..pos + len)
+    }
+
+    fn rfind_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        let bytes = haystack.as_bytes();
+        let len = AsRef::<[u8]>::as_ref(&self.literal).len();
+        (0..=bytes.len().checked_sub(len)?)
+            .rev()
+            .find(|&pos| haystack.is_boundary(pos) && self.matches_at(bytes, pos))
+            .map(|pos| pos..pos + len)
+    }
+
+    fn prefix_len(&mut self, haystack: &H) -> Option<usize> {
+        let len = AsRef::<[u8]>::as_ref(&self.literal).len();
+        self.matches_at(haystack.as_bytes(), 0).then_some(len)
+    }
+
+    fn suffix_len(&mut self, haystack: &H) -> Option<usize> {
+        let len = AsRef::<[u8]>::as_ref(&self.literal).len();
+        let pos = haystack.len().checked_sub(len)?;
+        self.matches_at(haystack.as_bytes(), pos).then_some(len)
+    }
+
+    fn describe(&self) -> String {
+        let literal = describe_literal(self.literal.as_ref());
+        format!("{literal} ignoring ASCII case")
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(AsRef::<[u8]>::as_ref(&self.literal).len())
+    }
+}
+
+// The simple, one char to one char, case folding: the lowercase of the
+// uppercase, so that 'ſ' and 's' or 'ς' and 'σ' fold together, keeping chars
+// whose case mapping takes several chars, such as 'ß', as they are.
+fn fold(c: char) -> char {
+    if c.is_ascii() {
+        return c.to_ascii_lowercase();
+    }
+    let upper = single(c.to_uppercase()).unwrap_or(c);
+    single(upper.to_lowercase()).unwrap_or(upper)
+}
+
+fn single(mut mapped: impl Iterator<Item = char>) -> Option<char> {
+    let first = mapped.next();
+    first.filter(|_| mapped.next().is_none())
+}
+
+// Matches a literal regardless of case under simple Unicode case folding. A
+// match has as many chars as the literal, but not always as many bytes.
+#[derive(Clone, Debug)]
+pub struct CaseFold {
+    literal: String,
+    folded: Vec<char>,
+}
+
+impl CaseFold {
+    pub fn new(literal: &str) -> Self {
+        Self {
+            literal: literal.to_owned(),
+            folded: literal.chars().map(fold).collect(),
+        }
+    }
+
+    // End of the match starting at `pos`.
+    fn forward_at(&self, text: &str, pos: usize) -> Option<usize> {
+        let mut chars = text[pos..].char_indices();
+        for &expected in &self.folded {
+            let (_, c) = chars.next()?;
+            if fold(c) != expected {
+                return None;
+            }
+        }
+        Some(pos + chars.offset())
+    }
+
+    // Start of the match ending at `pos`.
+    fn backward_at(&self, text: &str, pos: usize) -> Option<usize> {
+        let mut chars = text[..pos].chars();
+        for &expected in self.folded.iter().rev() {
+            if fold(chars.next_back()?) != expected {
+                return None;
+            }
+        }
+        Some(chars.as_str().len())
+    }
+}
+
+fn boundaries(text: &str) -> impl DoubleEndedIterator<Item = usize> + '_ {
+    text.char_indices()
+        .map(|(pos, _)| pos)
+        .chain(std::iter::once(text.len()))
+}
+
+impl Pattern<str> for CaseFold {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        boundaries(haystack).find_map(|pos| Some(pos..self.forward_at(haystack, pos)?))
+    }
+
+    fn rfind_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        boundaries(haystack)
+            .rev()
+            .find_map(|pos| Some(self.backward_at(haystack, pos)?..pos))
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.forward_at(haystack, 0)
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        let start = self.backward_at(haystack, haystack.len())?;
+        Some(haystack.len() - start)
+    }
+
+    fn describe(&self) -> String {
+        format!("'{}' ignoring case", self.literal.escape_debug())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteCursor, InwardStrategy, ParseCursor, PatternLoc};
+
+    #[test]
+    fn test_ignore_ascii_case() {
+        let data = "Error: a\nERROR: b\nerror: c";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward(IgnoreAsciiCase::new("error:"), PatternLoc::BeginningOnce)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "Error:");
+        let mut cursor = ParseCursor::new_empty_end(data);
+        cursor
+            .front_backward(
+                IgnoreAsciiCase::new("ERROR:"),
+                PatternLoc::FirstExcluded,
+                InwardStrategy::WholeData,
+            )
+            .unwrap();
+        assert_eq!(cursor.front_rem(), " c");
+
+        let mut cursor = ParseCursor::new_empty_start("AbabABx");
+        cursor
+            .front_forward(IgnoreAsciiCase::new("ab"), PatternLoc::BeginningMany)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "AbabAB");
+        let mut cursor = ParseCursor::new_empty_start("xAbaB");
+        cursor
+            .front_forward(IgnoreAsciiCase::new("ab"), PatternLoc::StartOfSuffixMany)
+            .unwrap();
+        assert_eq!(cursor.front_rem(), "AbaB");
+
+        let mut cursor = ByteCursor::new_empty_start(b"\xffContent-Type: x");
+        let err = cursor
+            .front_forward(
+                IgnoreAsciiCase::new(b"content-length"),
+                PatternLoc::FirstExcluded,
+            )
+            .unwrap_err();
+        assert_eq!(err.pattern(), "'content-length' ignoring ASCII case");
+        cursor
+            .front_forward(
+                IgnoreAsciiCase::new(b"CONTENT-TYPE"),
+                PatternLoc::FirstIncluded,
+            )
+            .unwrap();
+        assert_eq!(cursor.front_rem(), b": x");
+    }
+
+    #[test]
+    fn test_case_fold() {
+        assert_eq!(fold('Σ'), 'σ');
+        assert_eq!(fold('ς'), 'σ');
+        assert_eq!(fold('ſ'), 's');
+        assert_eq!(fold('ß'), 'ß');
+        assert_eq!(fold('\u{212A}'), 'k');
+
+        // The Kelvin sign takes three bytes where 'k' takes one.
+        let data = "\u{212A}elvin ΣΟΦΙΑ kelvin σοφια";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward(CaseFold::new("kelvin"), PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "\u{212A}elvin");
+        cursor.back_to_front();
+        cursor
+            .front_forward(CaseFold::new("Σοφια"), PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), " ΣΟΦΙΑ");
+
+        let mut cursor = ParseCursor::new_empty_end(data);
+        cursor
+            .back_backward(CaseFold::new("SOFIA"), PatternLoc::FirstExcluded)
+            .unwrap_err();
+        cursor
+            .back_backward(CaseFold::new("ΣΟΦΙΑ"), PatternLoc::BeginningOnce)
+            .unwrap()
+            .back_backward(CaseFold::new("KELVIN "), PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "kelvin σοφια");
+    }
+}
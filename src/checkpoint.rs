@@ -0,0 +1,143 @@
+use std::ops::{Deref, DerefMut};
+
+use crate::{Haystack, ParseCursor};
+
+// Where both ends of a cursor were. Only meaningful for cursors over the
+// same data as the one it was taken from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Checkpoint {
+    start: usize,
+    end: usize,
+}
+
+// Restores the cursor to where it was when the guard was made once dropped,
+// unless `commit` was called.
+pub struct CursorGuard<'g, 'a, H: ?Sized + Haystack = str> {
+    cursor: &'g mut ParseCursor<'a, H>,
+    checkpoint: Checkpoint,
+    committed: bool,
+}
+
+impl<'a, H: ?Sized + Haystack> ParseCursor<'a, H> {
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            start: self.cursor_range.start,
+            end: self.cursor_range.end,
+        }
+    }
+
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        let Checkpoint { start, end } = checkpoint;
+        assert!(self.bounds.start <= start && start <= end && end <= self.bounds.end);
+        assert!(self.data.get(start..end).is_some());
+        self.cursor_range = start..end;
+    }
+
+    pub fn guard(&mut self) -> CursorGuard<'_, 'a, H> {
+        CursorGuard {
+            checkpoint: self.checkpoint(),
+            cursor: self,
+            committed: false,
+        }
+    }
+}
+
+impl<H: ?Sized + Haystack> CursorGuard<'_, '_, H> {
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+
+    pub fn rollback(&mut self) {
+        self.cursor.restore(self.checkpoint);
+    }
+}
+
+impl<'a, H: ?Sized + Haystack> Deref for CursorGuard<'_, 'a, H> {
+    type Target = ParseCursor<'a, H>;
+
+    fn deref(&self) -> &Self::Target {
+        self.cursor
+    }
+}
+
+impl<H: ?Sized + Haystack> DerefMut for CursorGuard<'_, '_, H> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.cursor
+    }
+}
+
+impl<H: ?Sized + Haystack> Drop for CursorGuard<'_, '_, H> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.rollback();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CursorError, PatternLoc};
+
+    #[test]
+    fn test_restore_any_checkpoint() {
+        let mut cursor = ParseCursor::new_empty_start("key: value; other");
+        let start = cursor.checkpoint();
+        cursor
+            .front_forward(':', PatternLoc::FirstExcluded)
+            .unwrap();
+        let after_key = cursor.checkpoint();
+        assert_eq!(cursor.cursor(), "key");
+        cursor
+            .front_forward(';', PatternLoc::FirstIncluded)
+            .unwrap();
+        assert!(
+            cursor
+                .front_forward('=', PatternLoc::FirstExcluded)
+                .is_err()
+        );
+        cursor.restore(after_key);
+        assert_eq!(cursor.cursor(), "key");
+        cursor.restore(start);
+        assert_eq!(cursor.front_rem(), "key: value; other");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_restore_checks_bounds() {
+        let mut long = ParseCursor::new_full("a longer text");
+        let checkpoint = long.checkpoint();
+        long.restore(checkpoint);
+        ParseCursor::new_empty_start("short").restore(checkpoint);
+    }
+
+    fn number_or_word(cursor: &mut ParseCursor<'_>) -> Result<bool, CursorError> {
+        let mut guard = cursor.guard();
+        guard.back_to_front();
+        if guard
+            .front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningOnce)
+            .is_ok()
+        {
+            guard.front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningMany)?;
+            guard.commit();
+            return Ok(true);
+        }
+        guard.rollback();
+        guard.back_to_front();
+        guard.front_forward(char::is_alphabetic, PatternLoc::BeginningOnce)?;
+        guard.front_forward(char::is_alphabetic, PatternLoc::BeginningMany)?;
+        guard.commit();
+        Ok(false)
+    }
+
+    #[test]
+    fn test_guard_rolls_back_unless_committed() {
+        let mut cursor = ParseCursor::new_empty_start("42abc!");
+        assert_eq!(number_or_word(&mut cursor), Ok(true));
+        assert_eq!(cursor.cursor(), "42");
+        assert_eq!(number_or_word(&mut cursor), Ok(false));
+        assert_eq!(cursor.cursor(), "abc");
+        assert!(number_or_word(&mut cursor).is_err());
+        assert_eq!(cursor.cursor(), "abc");
+    }
+}
@@ -0,0 +1,268 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::{CursorError, Haystack, ParseCursor};
+
+// The shape of a move chain such as `|c| c.front_forward(...)?.back_forward(...)`,
+// which every combinator takes and returns, so they nest and plug into `step`.
+// All of them are atomic: on failure the cursor is left as it was.
+pub trait Step<'a, H: ?Sized + 'a = str>:
+    for<'c> FnMut(&'c mut ParseCursor<'a, H>) -> Result<&'c mut ParseCursor<'a, H>, CursorError>
+{
+}
+
+impl<'a, H: ?Sized + 'a, F> Step<'a, H> for F where
+    F: for<'c> FnMut(&'c mut ParseCursor<'a, H>) -> Result<&'c mut ParseCursor<'a, H>, CursorError>
+{
+}
+
+fn attempt<'a, H: ?Sized + 'a>(
+    cursor: &mut ParseCursor<'a, H>,
+    f: &mut impl Step<'a, H>,
+) -> Result<(), CursorError> {
+    let mut candidate = cursor.clone();
+    f(&mut candidate)?;
+    *cursor = candidate;
+    Ok(())
+}
+
+// Tries `first`, then `second` if it failed, returning the error of the last
+// one. See `alt!` for more alternatives.
+pub fn alt<'a, H: ?Sized + 'a>(
+    mut first: impl Step<'a, H>,
+    mut second: impl Step<'a, H>,
+) -> impl Step<'a, H> {
+    move |cursor: &mut ParseCursor<'a, H>| {
+        if attempt(cursor, &mut first).is_err() {
+            attempt(cursor, &mut second)?;
+        }
+        Ok(cursor)
+    }
+}
+
+#[macro_export]
+macro_rules! alt {
+    ($step:expr $(,)?) => {
+        $step
+    };
+    ($first:expr, $($rest:expr),+ $(,)?) => {
+        $crate::combinator::alt($first, $crate::alt!($($rest),+))
+    };
+}
+
+pub fn opt<'a, H: ?Sized + 'a>(mut f: impl Step<'a, H>) -> impl Step<'a, H> {
+    move |cursor: &mut ParseCursor<'a, H>| {
+        let _ = attempt(cursor, &mut f);
+        Ok(cursor)
+    }
+}
+
+fn count_bounds(count: impl RangeBounds<usize>) -> (usize, Option<usize>) {
+    let min = match count.start_bound() {
+        Bound::Included(&min) => min,
+        Bound::Excluded(&min) => min + 1,
+        Bound::Unbounded => 0,
+    };
+    let max = match count.end_bound() {
+        Bound::Included(&max) => Some(max),
+        Bound::Excluded(&max) => Some(max.saturating_sub(1)),
+        Bound::Unbounded => None,
+    };
+    (min, max)
+}
+
+// Runs `once` with the number of previous successes until it fails or `max`
+// is reached, failing if that is less than `min` times.
+fn repeat_between<'a, H: ?Sized + Haystack + 'a>(
+    cursor: &mut ParseCursor<'a, H>,
+    (min, max): (usize, Option<usize>),
+    mut once: impl FnMut(&mut ParseCursor<'a, H>, usize) -> Result<(), CursorError>,
+) -> Result<(), CursorError> {
+    let mut candidate = cursor.clone();
+    let mut done = 0;
+    while max.is_none_or(|max| done < max) {
+        let mut next = candidate.clone();
+        match once(&mut next, done) {
+            // Succeeding without moving would go on forever, which also
+            // covers `min`.
+            Ok(()) if next.checkpoint() == candidate.checkpoint() => break,
+            Ok(()) => {
+                candidate = next;
+                done += 1;
+            }
+            Err(err) if done < min => return Err(err),
+            Err(_) => break,
+        }
+    }
+    *cursor = candidate;
+    Ok(())
+}
+
+pub fn repeat<'a, H: ?Sized + Haystack + 'a>(
+    count: impl RangeBounds<usize>,
+    mut f: impl Step<'a, H>,
+) -> impl Step<'a, H> {
+    let bounds = count_bounds(count);
+    move |cursor: &mut ParseCursor<'a, H>| {
+        repeat_between(cursor, bounds, |c: &mut ParseCursor<'a, H>, _| {
+            f(c)?;
+            Ok(())
+        })?;
+        Ok(cursor)
+    }
+}
+
+// `count` items, with `separator` between each of them.
+pub fn separated<'a, H: ?Sized + Haystack + 'a>(
+    count: impl RangeBounds<usize>,
+    mut item: impl Step<'a, H>,
+    mut separator: impl Step<'a, H>,
+) -> impl Step<'a, H> {
+    let bounds = count_bounds(count);
+    move |cursor: &mut ParseCursor<'a, H>| {
+        repeat_between(cursor, bounds, |c: &mut ParseCursor<'a, H>, done| {
+            if done > 0 {
+                separator(c)?;
+            }
+            item(c)?;
+            Ok(())
+        })?;
+        Ok(cursor)
+    }
+}
+
+// Runs `prefix` then `f`, emptying the cursor in between so that it ends up
+// covering only what `f` moved over.
+pub fn preceded<'a, H: ?Sized + Haystack + 'a>(
+    mut prefix: impl Step<'a, H>,
+    mut f: impl Step<'a, H>,
+) -> impl Step<'a, H> {
+    move |cursor: &mut ParseCursor<'a, H>| {
+        let mut candidate = cursor.clone();
+        prefix(&mut candidate)?.back_to_front();
+        f(&mut candidate)?;
+        *cursor = candidate;
+        Ok(cursor)
+    }
+}
+
+// Runs `f` then `suffix`. The front end has to move past the suffix, so the
+// cursor covers both.
+pub fn terminated<'a, H: ?Sized + 'a>(
+    mut f: impl Step<'a, H>,
+    mut suffix: impl Step<'a, H>,
+) -> impl Step<'a, H> {
+    move |cursor: &mut ParseCursor<'a, H>| {
+        let mut candidate = cursor.clone();
+        f(&mut candidate)?;
+        suffix(&mut candidate)?;
+        *cursor = candidate;
+        Ok(cursor)
+    }
+}
+
+pub fn delimited<'a, H: ?Sized + Haystack + 'a>(
+    prefix: impl Step<'a, H>,
+    f: impl Step<'a, H>,
+    suffix: impl Step<'a, H>,
+) -> impl Step<'a, H> {
+    preceded(prefix, terminated(f, suffix))
+}
+
+// Succeeds without moving when `f` would fail here.
+pub fn not<'a, H: ?Sized + Haystack + 'a>(mut f: impl Step<'a, H>) -> impl Step<'a, H> {
+    move |cursor: &mut ParseCursor<'a, H>| {
+        if f(&mut cursor.clone()).is_ok() {
+            return Err(CursorError::invalid(
+                cursor.cursor_range.end,
+                "no match here",
+                "lookahead matched",
+            ));
+        }
+        Ok(cursor)
+    }
+}
+
+// Succeeds without moving when `f` would succeed here.
+pub fn peek<'a, H: ?Sized + 'a>(mut f: impl Step<'a, H>) -> impl Step<'a, H> {
+    move |cursor: &mut ParseCursor<'a, H>| {
+        f(&mut cursor.clone())?;
+        Ok(cursor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InwardStrategy, PatternLoc};
+
+    fn digits<'c, 'a>(c: &'c mut ParseCursor<'a>) -> Result<&'c mut ParseCursor<'a>, CursorError> {
+        c.front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningOnce)?
+            .front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningMany)
+    }
+
+    fn skip<'a>(literal: &'static str) -> impl Step<'a> {
+        move |c: &mut ParseCursor<'a>| {
+            c.back_forward(
+                literal,
+                PatternLoc::BeginningOnce,
+                InwardStrategy::WholeData,
+            )
+        }
+    }
+
+    fn keyword<'a>(word: &'static str) -> impl Step<'a> {
+        move |c: &mut ParseCursor<'a>| c.front_forward(word, PatternLoc::BeginningOnce)
+    }
+
+    #[test]
+    fn test_delimited_separated_list() {
+        let mut list = delimited(
+            skip("["),
+            separated(1.., digits, |c| {
+                c.front_forward(", ", PatternLoc::BeginningOnce)
+            }),
+            |c| c.front_forward(']', PatternLoc::BeginningOnce),
+        );
+        let mut cursor = ParseCursor::new_empty_start("[1, 22, 333] tail");
+        assert_eq!(cursor.step(&mut list), Ok("1, 22, 333]"));
+        assert_eq!(cursor.front_rem(), " tail");
+
+        let mut cursor = ParseCursor::new_empty_start("[] tail");
+        assert!(cursor.step(&mut list).is_err());
+        assert_eq!(cursor.front_rem(), "[] tail");
+    }
+
+    #[test]
+    fn test_repeat_counts() {
+        let mut cursor = ParseCursor::new_empty_start("aaaab");
+        assert!(cursor.step(repeat(5.., keyword("a"))).is_err());
+        assert_eq!(cursor.step(repeat(..3, keyword("a"))), Ok("aa"));
+        assert_eq!(cursor.step(repeat(1..=5, keyword("a"))), Ok("aaaa"));
+        assert_eq!(cursor.step(repeat(0.., keyword("a"))), Ok("aaaa"));
+        // A step that never moves does not loop forever.
+        let stay = repeat(3.., |c| c.front_forward('a', PatternLoc::BeginningMany));
+        assert_eq!(cursor.step(stay), Ok("aaaa"));
+    }
+
+    #[test]
+    fn test_alt_opt_not_peek() {
+        let mut cursor = ParseCursor::new_empty_start("let x");
+        let mut kw = crate::alt!(keyword("fn"), keyword("let"), keyword("const"));
+        assert_eq!(cursor.step(&mut kw), Ok("let"));
+        cursor.back_to_front();
+        assert!(cursor.step(&mut kw).is_err());
+        assert_eq!(cursor.step(opt(keyword("fn"))), Ok(""));
+
+        assert_eq!(cursor.step(peek(keyword(" x"))), Ok(""));
+        assert_eq!(cursor.front_rem(), " x");
+        let err = cursor.step(not(keyword(" "))).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected no match here (lookahead matched at offset 3)"
+        );
+        assert_eq!(
+            cursor.step(preceded(not(keyword("x")), keyword(" x"))),
+            Ok(" x")
+        );
+    }
+}
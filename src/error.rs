@@ -0,0 +1,223 @@
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::{Direction, End, Failed, Haystack, Pattern, PatternLoc};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CursorError {
+    offset: usize,
+    // Which end moved, and how, when the error comes from a move.
+    end: Option<End>,
+    direction: Option<Direction>,
+    loc: Option<PatternLoc>,
+    kind: ErrorKind,
+    pattern: String,
+    context: Vec<Cow<'static, str>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    // A move did not find its pattern.
+    NotFound,
+    // The cursor failed a check, such as converting its contents to a value.
+    Invalid { reason: String },
+    // A failure converted from `Failed`, which does not say where it happened.
+    Unlocated,
+}
+
+impl CursorError {
+    pub(crate) fn new(
+        offset: usize,
+        end: End,
+        direction: Direction,
+        loc: PatternLoc,
+        pattern: String,
+    ) -> Self {
+        Self {
+            offset,
+            end: Some(end),
+            direction: Some(direction),
+            loc: Some(loc),
+            kind: ErrorKind::NotFound,
+            pattern,
+            context: Vec::new(),
+        }
+    }
+
+    // A move that found nothing, or that could not search at all.
+    pub(crate) fn failed_move<H: ?Sized + Haystack>(
+        offset: usize,
+        end: End,
+        direction: Direction,
+        loc: PatternLoc,
+        pattern: &impl Pattern<H>,
+    ) -> Self {
+        if pattern.supports(loc) {
+            Self::new(offset, end, direction, loc, pattern.describe())
+        } else {
+            Self::rejected(
+                offset,
+                end,
+                direction,
+                Some(loc),
+                pattern.describe(),
+                format_args!("{loc:?} not supported"),
+            )
+        }
+    }
+
+    // A move of `end` in `direction` refused before it could happen, such as
+    // one by more lines than there are.
+    pub(crate) fn rejected(
+        offset: usize,
+        end: End,
+        direction: Direction,
+        loc: Option<PatternLoc>,
+        expected: impl Into<String>,
+        reason: impl fmt::Display,
+    ) -> Self {
+        Self {
+            end: Some(end),
+            direction: Some(direction),
+            loc,
+            ..Self::invalid(offset, expected, reason)
+        }
+    }
+
+    // A failed check at `offset` that is not about moving an end, as for a
+    // token that does not convert.
+    pub fn invalid(offset: usize, expected: impl Into<String>, reason: impl fmt::Display) -> Self {
+        Self {
+            offset,
+            end: None,
+            direction: None,
+            loc: None,
+            kind: ErrorKind::Invalid {
+                reason: reason.to_string(),
+            },
+            pattern: expected.into(),
+            context: Vec::new(),
+        }
+    }
+
+    // Byte offset into `data` of the end that failed to move, or of where
+    // the failed check looked. Unlocated errors have none and give 0.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    // The end that failed to move and its direction, None for errors that do
+    // not come from a move: checks made with `invalid` and unlocated errors.
+    pub fn end(&self) -> Option<End> {
+        self.end
+    }
+
+    pub fn direction(&self) -> Option<Direction> {
+        self.direction
+    }
+
+    // The location the move searched for, also None for moves by lines.
+    pub fn loc(&self) -> Option<PatternLoc> {
+        self.loc
+    }
+
+    pub fn pattern(&self) -> &str {
+        &self.pattern
+    }
+
+    // Labels attached while the error bubbled up, innermost first.
+    pub fn context(&self) -> impl DoubleEndedIterator<Item = &str> {
+        self.context.iter().map(|label| label.as_ref())
+    }
+
+    pub fn push_context(&mut self, label: impl Into<Cow<'static, str>>) {
+        self.context.push(label.into());
+    }
+}
+
+pub trait Context {
+    fn context(self, label: impl Into<Cow<'static, str>>) -> Self;
+
+    fn with_context<L: Into<Cow<'static, str>>>(self, label: impl FnOnce() -> L) -> Self;
+}
+
+impl<T> Context for Result<T, CursorError> {
+    fn context(self, label: impl Into<Cow<'static, str>>) -> Self {
+        self.map_err(|mut err| {
+            err.push_context(label);
+            err
+        })
+    }
+
+    fn with_context<L: Into<Cow<'static, str>>>(self, label: impl FnOnce() -> L) -> Self {
+        self.map_err(|mut err| {
+            err.push_context(label());
+            err
+        })
+    }
+}
+
+impl fmt::Display for End {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            End::Front => "front",
+            End::Back => "back",
+        })
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Direction::Forward => "forward",
+            Direction::Backward => "backward",
+        })
+    }
+}
+
+impl fmt::Display for CursorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            ErrorKind::Unlocated => f.write_str("failed")?,
+            _ => write!(f, "expected {}", self.pattern)?,
+        }
+        for label in &self.context {
+            write!(f, " while {label}")?;
+        }
+        match &self.kind {
+            ErrorKind::NotFound => match (self.loc, self.end, self.direction) {
+                (Some(loc), Some(end), Some(direction)) => write!(
+                    f,
+                    " ({loc:?} moving {end} end {direction} at offset {})",
+                    self.offset
+                ),
+                _ => write!(f, " (at offset {})", self.offset),
+            },
+            ErrorKind::Invalid { reason } => {
+                write!(f, " ({reason} at offset {})", self.offset)
+            }
+            ErrorKind::Unlocated => Ok(()),
+        }
+    }
+}
+
+impl std::error::Error for CursorError {}
+
+impl From<Failed> for CursorError {
+    fn from(_: Failed) -> Self {
+        Self {
+            kind: ErrorKind::Unlocated,
+            ..Self::invalid(0, "", "")
+        }
+    }
+}
+
+impl From<CursorError> for Failed {
+    fn from(_: CursorError) -> Self {
+        Failed
+    }
+}
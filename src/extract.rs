@@ -0,0 +1,216 @@
+use std::any::type_name;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{CursorError, FallBack, ParseCursor, PatternLoc};
+
+// Values that can be read off the front of a cursor. `extract` is handed an
+// empty cursor sitting at the old front; it moves the front end over its
+// token and builds the value, usually from the cursor contents.
+pub trait Extract<'a>: Sized {
+    fn extract(cursor: &mut ParseCursor<'a>) -> Result<Self, CursorError>;
+}
+
+impl<'a> ParseCursor<'a> {
+    pub fn parse<T>(&self) -> Result<T, CursorError>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.cursor()
+            .parse()
+            .map_err(|err| CursorError::invalid(self.cursor_range.start, type_name::<T>(), err))
+    }
+
+    // On success the cursor covers exactly the extracted token, even when
+    // `T::extract` itself takes several values. On failure it is left as is.
+    pub fn take<T: Extract<'a>>(&mut self) -> Result<T, CursorError> {
+        let mut candidate = self.clone();
+        candidate.back_to_front();
+        let start = candidate.cursor_range.start;
+        let value = T::extract(&mut candidate)?;
+        candidate.cursor_range.start = start;
+        *self = candidate;
+        Ok(value)
+    }
+
+    // Like `step`, but yields whatever `f` returns instead of the cursor contents.
+    pub fn step_value<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, CursorError>,
+    ) -> Result<T, CursorError> {
+        let mut candidate = self.clone();
+        let value = f(&mut candidate)?;
+        *self = candidate;
+        Ok(value)
+    }
+}
+
+fn is_digit(c: char) -> bool {
+    c.is_ascii_digit()
+}
+
+fn scan_integer(cursor: &mut ParseCursor<'_>) {
+    cursor.front_forward_or(
+        ['+', '-'],
+        PatternLoc::BeginningOnce,
+        FallBack::StayAtBeginning,
+    );
+    cursor.front_forward_or(
+        is_digit,
+        PatternLoc::BeginningMany,
+        FallBack::StayAtBeginning,
+    );
+}
+
+fn scan_float(cursor: &mut ParseCursor<'_>) {
+    scan_integer(cursor);
+    if cursor.front_forward('.', PatternLoc::BeginningOnce).is_ok() {
+        cursor.front_forward_or(
+            is_digit,
+            PatternLoc::BeginningMany,
+            FallBack::StayAtBeginning,
+        );
+    }
+    // The exponent only belongs to the number if digits follow it.
+    let _ = cursor.step(|c| {
+        c.front_forward(['e', 'E'], PatternLoc::BeginningOnce)?
+            .front_forward_or(
+                ['+', '-'],
+                PatternLoc::BeginningOnce,
+                FallBack::StayAtBeginning,
+            )
+            .front_forward(is_digit, PatternLoc::BeginningOnce)?
+            .front_forward(is_digit, PatternLoc::BeginningMany)
+    });
+}
+
+fn scan_word(cursor: &mut ParseCursor<'_>) {
+    cursor.front_forward_or(
+        |c: char| c.is_alphanumeric() || c == '_',
+        PatternLoc::BeginningMany,
+        FallBack::StayAtBeginning,
+    );
+}
+
+fn scan_char(cursor: &mut ParseCursor<'_>) {
+    cursor.front_forward_or(
+        |_: char| true,
+        PatternLoc::BeginningOnce,
+        FallBack::StayAtBeginning,
+    );
+}
+
+macro_rules! impl_extract_via_from_str {
+    ($scan:ident: $($ty:ty),*) => {
+        $(
+            impl<'a> Extract<'a> for $ty {
+                fn extract(cursor: &mut ParseCursor<'a>) -> Result<Self, CursorError> {
+                    $scan(cursor);
+                    cursor.parse()
+                }
+            }
+        )*
+    };
+}
+
+impl_extract_via_from_str!(scan_integer: i8, i16, i32, i64, i128, isize);
+impl_extract_via_from_str!(scan_integer: u8, u16, u32, u64, u128, usize);
+impl_extract_via_from_str!(scan_float: f32, f64);
+impl_extract_via_from_str!(scan_word: bool);
+impl_extract_via_from_str!(scan_char: char);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Context, ErrorKind, InwardStrategy};
+
+    #[test]
+    fn test_take_primitives() {
+        let mut cursor = ParseCursor::new_empty_start("-42 3.5e2 1e true x");
+        assert_eq!(cursor.take::<i32>(), Ok(-42));
+        assert_eq!(cursor.cursor(), "-42");
+        cursor
+            .front_forward(' ', PatternLoc::BeginningMany)
+            .unwrap();
+        assert_eq!(cursor.take::<f64>(), Ok(350.0));
+        cursor
+            .front_forward(' ', PatternLoc::BeginningMany)
+            .unwrap();
+        // A dangling exponent is left for the next move.
+        assert_eq!(cursor.take::<f32>(), Ok(1.0));
+        assert_eq!(cursor.front_rem(), "e true x");
+        cursor
+            .front_forward("e ", PatternLoc::BeginningOnce)
+            .unwrap();
+        assert_eq!(cursor.take::<bool>(), Ok(true));
+        cursor
+            .front_forward(' ', PatternLoc::BeginningMany)
+            .unwrap();
+        assert_eq!(cursor.take::<char>(), Ok('x'));
+        assert!(cursor.take::<char>().is_err());
+    }
+
+    #[test]
+    fn test_invalid_value_reports_position() {
+        let mut cursor = ParseCursor::new_empty_start("port: 99999");
+        cursor
+            .back_forward(
+                "port: ",
+                PatternLoc::FirstIncluded,
+                InwardStrategy::WholeData,
+            )
+            .unwrap();
+        let err = cursor.take::<u16>().context("parsing port").unwrap_err();
+        assert_eq!(err.offset(), 6);
+        assert_eq!(
+            err.kind(),
+            &ErrorKind::Invalid {
+                reason: "number too large to fit in target type".to_owned()
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "expected u16 while parsing port (number too large to fit in target type at offset 6)"
+        );
+        // The failed take left the cursor untouched.
+        assert_eq!(cursor.front_rem(), "99999");
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct Point {
+        x: i64,
+        y: i64,
+    }
+
+    impl<'a> Extract<'a> for Point {
+        fn extract(cursor: &mut ParseCursor<'a>) -> Result<Self, CursorError> {
+            cursor.front_forward('(', PatternLoc::BeginningOnce)?;
+            let x = cursor.take()?;
+            cursor.front_forward(',', PatternLoc::BeginningOnce)?;
+            let y = cursor.take()?;
+            cursor.front_forward(')', PatternLoc::BeginningOnce)?;
+            Ok(Point { x, y })
+        }
+    }
+
+    #[test]
+    fn test_custom_extract_composes() {
+        let mut cursor = ParseCursor::new_empty_start("(1,-2)(3,x)");
+        assert_eq!(cursor.take::<Point>(), Ok(Point { x: 1, y: -2 }));
+        assert_eq!(cursor.cursor(), "(1,-2)");
+        let err = cursor.take::<Point>().unwrap_err();
+        assert_eq!(err.offset(), 9);
+        assert_eq!(cursor.front_rem(), "(3,x)");
+
+        let mut cursor = ParseCursor::new_empty_start("w=3");
+        let (name, value) = cursor
+            .step_value(|c| {
+                let name = c.front_forward('=', PatternLoc::FirstExcluded)?.cursor();
+                c.front_forward('=', PatternLoc::BeginningOnce)?;
+                Ok((name, c.take::<u8>()?))
+            })
+            .unwrap();
+        assert_eq!((name, value), ("w", 3));
+    }
+}
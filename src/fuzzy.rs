@@ -0,0 +1,209 @@
+use std::ops::Range;
+
+use crate::Pattern;
+
+// Matches text within `max_distance` edits (Levenshtein, counted in chars) of
+// a literal. A search takes the first place the distance gets low enough, then
+// keeps going as long as that does not make it higher, so `EROR: x` matches
+// `ERROR:` as `EROR:` rather than `EROR`. The other end is wherever gives the
+// lowest distance. Pass it with `by_ref` to read `distance` after the move.
+#[derive(Clone, Debug)]
+pub struct Fuzzy {
+    literal: String,
+    chars: Vec<char>,
+    max_distance: usize,
+    distance: Option<usize>,
+}
+
+// The best alignment of the literal so far ending at a text position.
+#[derive(Clone, Copy)]
+struct Cell {
+    cost: usize,
+    // Where in the text it began.
+    start: usize,
+}
+
+// The first of the two on a tie.
+fn cheaper(first: Cell, second: Cell) -> Cell {
+    if second.cost < first.cost {
+        second
+    } else {
+        first
+    }
+}
+
+impl Fuzzy {
+    pub fn new(literal: &str, max_distance: usize) -> Self {
+        Self {
+            literal: literal.to_owned(),
+            chars: literal.chars().collect(),
+            max_distance,
+            distance: None,
+        }
+    }
+
+    // Distance of the match of the last search, `None` if it failed.
+    pub fn distance(&self) -> Option<usize> {
+        self.distance
+    }
+
+    // Scans `text`, given as the position right after each char in the
+    // scanning order starting at `origin`, against `needle` in that same
+    // order. Returns where the match begins and ends, and its distance.
+    fn scan(
+        &mut self,
+        needle: &[char],
+        origin: usize,
+        mut text: impl Iterator<Item = (usize, char)>,
+        anchored: bool,
+    ) -> Option<Range<usize>> {
+        self.distance = None;
+        let mut column: Vec<Cell> = (0..=needle.len())
+            .map(|i| Cell {
+                cost: i,
+                start: origin,
+            })
+            .collect();
+        let mut best: Option<(Cell, usize)> = None;
+        let mut consumed = 0;
+        let mut pos = origin;
+        loop {
+            let last = column[needle.len()];
+            match best {
+                Some((cell, _)) if last.cost > cell.cost => break,
+                Some(_) => best = Some((last, pos)),
+                None if last.cost <= self.max_distance => best = Some((last, pos)),
+                None => (),
+            }
+            let Some((next_pos, c)) = text.next() else {
+                break;
+            };
+            consumed += 1;
+            let mut diagonal = column[0];
+            column[0] = if anchored {
+                Cell {
+                    cost: consumed,
+                    start: origin,
+                }
+            } else {
+                Cell {
+                    cost: 0,
+                    start: next_pos,
+                }
+            };
+            for (i, &expected) in needle.iter().enumerate() {
+                let substitute = Cell {
+                    cost: diagonal.cost + usize::from(expected != c),
+                    start: diagonal.start,
+                };
+                let skip_text = Cell {
+                    cost: column[i + 1].cost + 1,
+                    ..column[i + 1]
+                };
+                let skip_needle = Cell {
+                    cost: column[i].cost + 1,
+                    ..column[i]
+                };
+                diagonal = column[i + 1];
+                column[i + 1] = cheaper(cheaper(substitute, skip_text), skip_needle);
+            }
+            pos = next_pos;
+        }
+        let (cell, end) = best?;
+        self.distance = Some(cell.cost);
+        Some(cell.start..end)
+    }
+
+    fn forward(&mut self, haystack: &str, anchored: bool) -> Option<Range<usize>> {
+        let needle = self.chars.clone();
+        let text = haystack.char_indices().map(|(i, c)| (i + c.len_utf8(), c));
+        self.scan(&needle, 0, text, anchored)
+    }
+
+    // The returned range is reversed, from the end of the match to its start.
+    fn backward(&mut self, haystack: &str, anchored: bool) -> Option<Range<usize>> {
+        let needle: Vec<char> = self.chars.iter().rev().copied().collect();
+        let text = haystack.char_indices().rev();
+        let found = self.scan(&needle, haystack.len(), text, anchored)?;
+        Some(found.end..found.start)
+    }
+}
+
+impl Pattern<str> for Fuzzy {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        self.forward(haystack, false)
+    }
+
+    fn rfind_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        self.backward(haystack, false)
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.forward(haystack, true).map(|found| found.end)
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.backward(haystack, true).map(|found| found.len())
+    }
+
+    fn describe(&self) -> String {
+        format!(
+            "'{}' within {} edits",
+            self.literal.escape_debug(),
+            self.max_distance
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InwardStrategy, ParseCursor, PatternLoc};
+
+    #[test]
+    fn test_fuzzy_search() {
+        let mut error = Fuzzy::new("ERROR:", 1);
+        assert_eq!(error.find_in("x EROR: bad"), Some(2..7));
+        assert_eq!(error.distance(), Some(1));
+        assert_eq!(error.find_in("ERROR: exact"), Some(0..6));
+        assert_eq!(error.distance(), Some(0));
+        assert_eq!(error.find_in("ERR: too far"), None);
+        assert_eq!(error.distance(), None);
+
+        let mut column = Fuzzy::new("column", 2);
+        // "colun" is one edit away, "colunm" two.
+        assert_eq!(column.rfind_in("column 1, colunm 2"), Some(10..15));
+        assert_eq!(column.distance(), Some(1));
+        assert_eq!(column.prefix_len("colunn 2"), Some(6));
+        assert_eq!(column.suffix_len("at côlumn"), Some(7));
+        assert_eq!(column.distance(), Some(1));
+    }
+
+    #[test]
+    fn test_fuzzy_moves() {
+        let data = "EROR: disk full (at line 3, colunm 9)";
+        let mut level = Fuzzy::new("ERROR:", 1);
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward(level.by_ref(), PatternLoc::BeginningOnce)
+            .unwrap();
+        assert_eq!((cursor.cursor(), level.distance()), ("EROR:", Some(1)));
+
+        let mut column = Fuzzy::new("column ", 2);
+        cursor
+            .back_forward(
+                column.by_ref(),
+                PatternLoc::FirstIncluded,
+                InwardStrategy::WholeData,
+            )
+            .unwrap();
+        assert_eq!(cursor.front_rem(), "9)");
+        assert_eq!(column.distance(), Some(2));
+
+        let mut cursor = ParseCursor::new_empty_start(data);
+        let err = cursor
+            .front_forward(Fuzzy::new("WARNING:", 2), PatternLoc::FirstExcluded)
+            .unwrap_err();
+        assert_eq!(err.pattern(), "'WARNING:' within 2 edits");
+    }
+}
@@ -0,0 +1,78 @@
+use std::ops::Range;
+
+mod private {
+    pub trait Sealed {}
+}
+
+pub trait Haystack: private::Sealed {
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_boundary(&self, index: usize) -> bool;
+
+    fn as_bytes(&self) -> &[u8];
+
+    fn get(&self, range: Range<usize>) -> Option<&Self>;
+
+    /// # Safety
+    ///
+    /// `range` must be in order, in bounds and on boundaries, i.e. `get`
+    /// must return `Some` for it.
+    unsafe fn get_unchecked(&self, range: Range<usize>) -> &Self;
+
+    fn slice(&self, range: Range<usize>) -> &Self {
+        self.get(range)
+            .expect("range out of bounds or not on a boundary")
+    }
+}
+
+impl private::Sealed for str {}
+
+impl Haystack for str {
+    fn len(&self) -> usize {
+        str::len(self)
+    }
+
+    fn is_boundary(&self, index: usize) -> bool {
+        self.is_char_boundary(index)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        str::as_bytes(self)
+    }
+
+    fn get(&self, range: Range<usize>) -> Option<&Self> {
+        str::get(self, range)
+    }
+
+    unsafe fn get_unchecked(&self, range: Range<usize>) -> &Self {
+        unsafe { str::get_unchecked(self, range) }
+    }
+}
+
+impl private::Sealed for [u8] {}
+
+impl Haystack for [u8] {
+    fn len(&self) -> usize {
+        <[u8]>::len(self)
+    }
+
+    fn is_boundary(&self, index: usize) -> bool {
+        index <= <[u8]>::len(self)
+    }
+
+    fn as_bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn get(&self, range: Range<usize>) -> Option<&Self> {
+        <[u8]>::get(self, range)
+    }
+
+    unsafe fn get_unchecked(&self, range: Range<usize>) -> &Self {
+        unsafe { <[u8]>::get_unchecked(self, range) }
+    }
+}
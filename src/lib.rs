@@ -1,23 +1,108 @@
 // Not copy to prevent logic errors
-#[derive(Clone, Debug)]
-pub struct ParseCursor<'a> {
-    data: &'a str,
+pub struct ParseCursor<'a, H: ?Sized = str> {
+    data: &'a H,
     cursor_range: Range<usize>,
+    // The part of `data` the ends may move in, all of it unless restricted.
+    bounds: Range<usize>,
+    // Built on the first position query, and shared by every clone.
+    line_index: Arc<OnceLock<LineIndex>>,
 }
 
+pub type ByteCursor<'a> = ParseCursor<'a, [u8]>;
+
+impl<H: ?Sized> Clone for ParseCursor<'_, H> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data,
+            cursor_range: self.cursor_range.clone(),
+            bounds: self.bounds.clone(),
+            line_index: self.line_index.clone(),
+        }
+    }
+}
+
+impl<H: ?Sized + fmt::Debug> fmt::Debug for ParseCursor<'_, H> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ParseCursor")
+            .field("data", &self.data)
+            .field("cursor_range", &self.cursor_range)
+            .field("bounds", &self.bounds)
+            .finish()
+    }
+}
+
+// The former error of the moves, still usable by code returning or matching
+// on it: `?` converts between it and `CursorError` both ways.
 #[derive(Debug)]
 pub struct Failed;
 
+use std::borrow::Cow;
+use std::fmt;
 use std::ops::Range;
+use std::sync::{Arc, OnceLock};
+
+mod any_of;
+mod balanced;
+mod case;
+mod checkpoint;
+pub mod combinator;
+mod error;
+mod extract;
+mod fuzzy;
+mod haystack;
+mod lines;
+mod lookahead;
+mod nested;
+mod pattern;
+mod position;
+mod quoted;
+mod regex;
+mod render;
+mod span;
+mod stream;
+mod template;
+mod window;
 
-use stable_string_patterns_method::{IntoSearchable, Searchable, StrPatternExt};
+pub use any_of::AnyOf;
+pub use balanced::Balanced;
+pub use case::{CaseFold, IgnoreAsciiCase};
+pub use checkpoint::{Checkpoint, CursorGuard};
+pub use combinator::Step;
+pub use error::{Context, CursorError, ErrorKind};
+pub use extract::Extract;
+pub use fuzzy::Fuzzy;
+pub use haystack::Haystack;
+pub use pattern::{ByRef, Pattern};
+pub use position::{LineIndex, Position};
+pub use quoted::{Escape, Quoted};
+pub use regex::{Regex, RegexError};
+pub use render::{Colored, Diagnostic};
+pub use span::Span;
+pub use stream::{StreamCursor, StreamError};
+pub use window::{Never, Window};
 
-#[derive(Debug, Clone, Copy)]
-enum Direction {
+pub use parsebuf_macros::{Parse, template};
+
+// Lets the macros name this crate as `::parsebuf` from its own tests too.
+extern crate self as parsebuf;
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::template::{FieldSpec, field, hole, literal, parse, text, value};
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
     Forward,
     Backward,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum End {
+    Front,
+    Back,
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PatternLoc {
     FirstExcluded,
@@ -27,65 +112,154 @@ pub enum PatternLoc {
     LastExcluded,
     EndOfLast,
     StartOfSuffixMany,
+    // The occurrence of the given index, counting from 0 in the direction of
+    // the move like `First*`, or from the far end of the searched view like
+    // `LastExcluded` and `EndOfLast`. Occurrences do not overlap.
+    NthExcluded(usize),
+    NthIncluded(usize),
+    NthLastExcluded(usize),
+    NthLastIncluded(usize),
+}
+
+// Both end by matching the last repetition again, so that patterns keeping
+// state about their last match, such as `AnyOf`, describe it rather than the
+// probe that failed.
+fn trimmed_start_len<H: ?Sized + Haystack>(haystack: &H, pattern: &mut impl Pattern<H>) -> usize {
+    let mut trimmed = 0;
+    let mut last = None;
+    while let Some(len @ 1..) = pattern.prefix_len(haystack.slice(trimmed..haystack.len())) {
+        last = Some(trimmed);
+        trimmed += len;
+    }
+    if let Some(last) = last {
+        pattern.prefix_len(haystack.slice(last..haystack.len()));
+    }
+    trimmed
+}
+
+fn trimmed_end_len<H: ?Sized + Haystack>(haystack: &H, pattern: &mut impl Pattern<H>) -> usize {
+    let mut trimmed = 0;
+    let mut last = None;
+    while let Some(len @ 1..) = pattern.suffix_len(haystack.slice(0..haystack.len() - trimmed)) {
+        last = Some(trimmed);
+        trimmed += len;
+    }
+    if let Some(last) = last {
+        pattern.suffix_len(haystack.slice(0..haystack.len() - last));
+    }
+    trimmed
 }
 
-fn find_directional_offset(
-    haystack: &str,
-    pattern: impl Searchable,
+fn nth_from_start<H: ?Sized + Haystack>(
+    haystack: &H,
+    pattern: &mut impl Pattern<H>,
+    n: usize,
+) -> Option<Range<usize>> {
+    let mut from = 0;
+    for _ in 0..n {
+        let found = pattern.find_in(haystack.slice(from..haystack.len()))?;
+        from += found.end;
+        // An empty match would be found again.
+        if found.is_empty() {
+            from += (1..=haystack.len() - from).find(|&len| haystack.is_boundary(from + len))?;
+        }
+    }
+    let found = pattern.find_in(haystack.slice(from..haystack.len()))?;
+    Some(from + found.start..from + found.end)
+}
+
+fn nth_from_end<H: ?Sized + Haystack>(
+    haystack: &H,
+    pattern: &mut impl Pattern<H>,
+    n: usize,
+) -> Option<Range<usize>> {
+    let mut to = haystack.len();
+    for _ in 0..n {
+        let found = pattern.rfind_in(haystack.slice(0..to))?;
+        to = found.start;
+        if found.is_empty() {
+            to -= (1..=to).find(|&len| haystack.is_boundary(to - len))?;
+        }
+    }
+    pattern.rfind_in(haystack.slice(0..to))
+}
+
+fn find_directional_offset<H: ?Sized + Haystack>(
+    haystack: &H,
+    mut pattern: impl Pattern<H>,
     loc: PatternLoc,
     direction: Direction,
 ) -> Option<usize> {
+    if !pattern.supports(loc) {
+        return None;
+    }
     let from_start_offset_to_end_offset = |offset_from_beg| haystack.len() - offset_from_beg;
-    let from_end_offset_to_start_offset = |offset_from_end| haystack.len() - offset_from_end;
-    let offset_of_sub_end = |(offset_of_sub, sub): (usize, &str)| offset_of_sub + sub.len();
     match (loc, direction) {
-        (PatternLoc::FirstExcluded, Direction::Forward) => haystack.find_(pattern),
-        (PatternLoc::FirstExcluded, Direction::Backward) => haystack
-            .rmatch_indices_(pattern)
-            .next()
-            .map(offset_of_sub_end)
-            .map(from_start_offset_to_end_offset),
-        (PatternLoc::FirstIncluded, Direction::Forward) => haystack
-            .match_indices_(pattern)
-            .next()
-            .map(offset_of_sub_end),
-        (PatternLoc::FirstIncluded, Direction::Backward) => haystack
-            .rfind_(pattern)
-            .map(from_start_offset_to_end_offset),
+        (PatternLoc::FirstExcluded, Direction::Forward) => {
+            pattern.find_in(haystack).map(|sub| sub.start)
+        }
+        (PatternLoc::FirstExcluded, Direction::Backward) => pattern
+            .rfind_in(haystack)
+            .map(|sub| from_start_offset_to_end_offset(sub.end)),
+        (PatternLoc::FirstIncluded, Direction::Forward) => {
+            pattern.find_in(haystack).map(|sub| sub.end)
+        }
+        (PatternLoc::FirstIncluded, Direction::Backward) => pattern
+            .rfind_in(haystack)
+            .map(|sub| from_start_offset_to_end_offset(sub.start)),
         (PatternLoc::BeginningMany, Direction::Forward) => {
-            let rem = haystack.trim_start_matches_(pattern);
-            Some(from_end_offset_to_start_offset(rem.len()))
+            Some(trimmed_start_len(haystack, &mut pattern))
         }
         (PatternLoc::BeginningMany, Direction::Backward) => {
-            let rem = haystack.trim_end_matches_(pattern);
-            Some(from_start_offset_to_end_offset(rem.len()))
+            Some(trimmed_end_len(haystack, &mut pattern))
+        }
+        (PatternLoc::BeginningOnce, Direction::Forward) => pattern.prefix_len(haystack),
+        (PatternLoc::BeginningOnce, Direction::Backward) => pattern.suffix_len(haystack),
+        (PatternLoc::LastExcluded, Direction::Forward) => {
+            pattern.rfind_in(haystack).map(|sub| sub.start)
+        }
+        (PatternLoc::LastExcluded, Direction::Backward) => pattern
+            .rfind_in(haystack)
+            .map(|sub| from_start_offset_to_end_offset(sub.end)),
+        (PatternLoc::EndOfLast, Direction::Forward) => {
+            pattern.rfind_in(haystack).map(|sub| sub.end)
+        }
+        (PatternLoc::EndOfLast, Direction::Backward) => pattern
+            .find_in(haystack)
+            .map(|sub| from_start_offset_to_end_offset(sub.start)),
+        (PatternLoc::StartOfSuffixMany, Direction::Forward) => Some(
+            from_start_offset_to_end_offset(trimmed_end_len(haystack, &mut pattern)),
+        ),
+        (PatternLoc::StartOfSuffixMany, Direction::Backward) => Some(
+            from_start_offset_to_end_offset(trimmed_start_len(haystack, &mut pattern)),
+        ),
+        (PatternLoc::NthExcluded(n), Direction::Forward) => {
+            nth_from_start(haystack, &mut pattern, n).map(|sub| sub.start)
         }
-        (PatternLoc::BeginningOnce, Direction::Forward) => {
-            let rem = haystack.strip_prefix_(pattern)?;
-            Some(from_end_offset_to_start_offset(rem.len()))
+        (PatternLoc::NthExcluded(n), Direction::Backward) => {
+            nth_from_end(haystack, &mut pattern, n)
+                .map(|sub| from_start_offset_to_end_offset(sub.end))
         }
-        (PatternLoc::BeginningOnce, Direction::Backward) => {
-            let rem = haystack.strip_suffix_(pattern)?;
-            Some(from_start_offset_to_end_offset(rem.len()))
+        (PatternLoc::NthIncluded(n), Direction::Forward) => {
+            nth_from_start(haystack, &mut pattern, n).map(|sub| sub.end)
         }
-        (PatternLoc::LastExcluded, Direction::Forward) => haystack.rfind_(pattern),
-        (PatternLoc::LastExcluded, Direction::Backward) => haystack
-            .rmatch_indices_(pattern)
-            .next()
-            .map(offset_of_sub_end)
-            .map(from_start_offset_to_end_offset),
-        (PatternLoc::EndOfLast, Direction::Forward) => haystack
-            .rmatch_indices_(pattern)
-            .next()
-            .map(offset_of_sub_end),
-        (PatternLoc::EndOfLast, Direction::Backward) => {
-            haystack.find_(pattern).map(from_start_offset_to_end_offset)
+        (PatternLoc::NthIncluded(n), Direction::Backward) => {
+            nth_from_end(haystack, &mut pattern, n)
+                .map(|sub| from_start_offset_to_end_offset(sub.start))
         }
-        (PatternLoc::StartOfSuffixMany, Direction::Forward) => {
-            Some(haystack.trim_end_matches_(pattern).len())
+        (PatternLoc::NthLastExcluded(n), Direction::Forward) => {
+            nth_from_end(haystack, &mut pattern, n).map(|sub| sub.start)
         }
-        (PatternLoc::StartOfSuffixMany, Direction::Backward) => {
-            Some(haystack.trim_start_matches_(pattern).len())
+        (PatternLoc::NthLastExcluded(n), Direction::Backward) => {
+            nth_from_start(haystack, &mut pattern, n)
+                .map(|sub| from_start_offset_to_end_offset(sub.end))
+        }
+        (PatternLoc::NthLastIncluded(n), Direction::Forward) => {
+            nth_from_end(haystack, &mut pattern, n).map(|sub| sub.end)
+        }
+        (PatternLoc::NthLastIncluded(n), Direction::Backward) => {
+            nth_from_start(haystack, &mut pattern, n)
+                .map(|sub| from_start_offset_to_end_offset(sub.start))
         }
     }
 }
@@ -101,32 +275,38 @@ pub enum FallBack {
     StayAtBeginning,
 }
 
-impl<'a> ParseCursor<'a> {
-    pub fn new_empty_start(data: &'a str) -> Self {
+impl<'a, H: ?Sized + Haystack> ParseCursor<'a, H> {
+    fn with_range(data: &'a H, cursor_range: Range<usize>) -> Self {
         Self {
             data,
-            cursor_range: Range { start: 0, end: 0 },
+            cursor_range,
+            bounds: 0..data.len(),
+            line_index: Arc::new(OnceLock::new()),
         }
     }
 
-    pub fn new_empty_end(data: &'a str) -> Self {
-        Self {
+    pub fn new_empty_start(data: &'a H) -> Self {
+        Self::with_range(data, Range { start: 0, end: 0 })
+    }
+
+    pub fn new_empty_end(data: &'a H) -> Self {
+        Self::with_range(
             data,
-            cursor_range: Range {
+            Range {
                 start: data.len(),
                 end: data.len(),
             },
-        }
+        )
     }
 
-    pub fn new_full(data: &'a str) -> Self {
-        Self {
+    pub fn new_full(data: &'a H) -> Self {
+        Self::with_range(
             data,
-            cursor_range: Range {
+            Range {
                 start: 0,
                 end: data.len(),
             },
-        }
+        )
     }
 
     pub fn front_to_back(&mut self) {
@@ -137,7 +317,7 @@ impl<'a> ParseCursor<'a> {
         self.cursor_range.start = self.cursor_range.end;
     }
 
-    pub fn data(&self) -> &'a str {
+    pub fn data(&self) -> &'a H {
         self.data
     }
 
@@ -146,12 +326,12 @@ impl<'a> ParseCursor<'a> {
     }
 
     #[cfg(not(feature = "use-unsafe"))]
-    pub fn cursor(&self) -> &'a str {
-        &self.data[self.cursor_range()]
+    pub fn cursor(&self) -> &'a H {
+        self.data.slice(self.cursor_range())
     }
 
     #[cfg(feature = "use-unsafe")]
-    pub fn cursor(&self) -> &'a str {
+    pub fn cursor(&self) -> &'a H {
         #[cfg(debug_assertions)]
         self.check_invariants();
         unsafe { self.data.get_unchecked(self.cursor_range()) }
@@ -161,61 +341,75 @@ impl<'a> ParseCursor<'a> {
     fn check_invariants(&self) {
         assert!(self.cursor_range.start <= self.cursor_range.end);
         assert!(self.data.get(self.cursor_range.clone()).is_some());
+        assert!(self.bounds.start <= self.cursor_range.start);
+        assert!(self.cursor_range.end <= self.bounds.end);
     }
 
     #[cfg(not(feature = "use-unsafe"))]
-    pub fn back_rem(&self) -> &'a str {
-        &self.data[..self.cursor_range.start]
+    pub fn back_rem(&self) -> &'a H {
+        self.data.slice(self.bounds.start..self.cursor_range.start)
     }
 
     #[cfg(feature = "use-unsafe")]
-    pub fn back_rem(&self) -> &'a str {
+    pub fn back_rem(&self) -> &'a H {
         #[cfg(debug_assertions)]
         self.check_invariants();
-        unsafe { self.data.get_unchecked(..self.cursor_range.start) }
+        unsafe {
+            self.data
+                .get_unchecked(self.bounds.start..self.cursor_range.start)
+        }
     }
 
     #[cfg(not(feature = "use-unsafe"))]
-    pub fn front_rem(&self) -> &'a str {
-        &self.data[self.cursor_range.end..]
+    pub fn front_rem(&self) -> &'a H {
+        self.data.slice(self.cursor_range.end..self.bounds.end)
     }
 
     #[cfg(feature = "use-unsafe")]
-    pub fn front_rem(&self) -> &'a str {
+    pub fn front_rem(&self) -> &'a H {
         #[cfg(debug_assertions)]
         self.check_invariants();
-        unsafe { self.data.get_unchecked(self.cursor_range.end..) }
+        unsafe {
+            self.data
+                .get_unchecked(self.cursor_range.end..self.bounds.end)
+        }
     }
 
     #[cfg(not(feature = "use-unsafe"))]
-    pub fn all_but_front_rem(&self) -> &'a str {
-        &self.data[..self.cursor_range.end]
+    pub fn all_but_front_rem(&self) -> &'a H {
+        self.data.slice(self.bounds.start..self.cursor_range.end)
     }
 
     #[cfg(feature = "use-unsafe")]
-    pub fn all_but_front_rem(&self) -> &'a str {
+    pub fn all_but_front_rem(&self) -> &'a H {
         #[cfg(debug_assertions)]
         self.check_invariants();
-        unsafe { self.data.get_unchecked(..self.cursor_range.end) }
+        unsafe {
+            self.data
+                .get_unchecked(self.bounds.start..self.cursor_range.end)
+        }
     }
 
     #[cfg(not(feature = "use-unsafe"))]
-    pub fn all_but_back_rem(&self) -> &'a str {
-        &self.data[self.cursor_range.start..]
+    pub fn all_but_back_rem(&self) -> &'a H {
+        self.data.slice(self.cursor_range.start..self.bounds.end)
     }
 
     #[cfg(feature = "use-unsafe")]
-    pub fn all_but_back_rem(&self) -> &'a str {
+    pub fn all_but_back_rem(&self) -> &'a H {
         #[cfg(debug_assertions)]
         self.check_invariants();
-        unsafe { self.data.get_unchecked(self.cursor_range.start..) }
+        unsafe {
+            self.data
+                .get_unchecked(self.cursor_range.start..self.bounds.end)
+        }
     }
 
-    pub fn split(&self) -> (&'a str, &'a str, &'a str) {
+    pub fn split(&self) -> (&'a H, &'a H, &'a H) {
         (self.back_rem(), self.cursor(), self.front_rem())
     }
 
-    pub fn extract(&self) -> (&'a str, Self, &'a str) {
+    pub fn extract(&self) -> (&'a H, Self, &'a H) {
         (
             self.back_rem(),
             Self::new_full(self.cursor()),
@@ -227,6 +421,20 @@ impl<'a> ParseCursor<'a> {
         self.extract().1
     }
 
+    fn failure(
+        &self,
+        end: End,
+        direction: Direction,
+        loc: PatternLoc,
+        pattern: &impl Pattern<H>,
+    ) -> CursorError {
+        let offset = match end {
+            End::Front => self.cursor_range.end,
+            End::Back => self.cursor_range.start,
+        };
+        CursorError::failed_move(offset, end, direction, loc, pattern)
+    }
+
     fn move_front_forward(&mut self, by: usize) {
         self.cursor_range.end += by;
     }
@@ -262,30 +470,26 @@ impl<'a> ParseCursor<'a> {
     }
 
     pub fn front_forward_by(&mut self, by: usize) -> &mut Self {
-        assert!(self.front_rem().is_char_boundary(by));
+        assert!(self.front_rem().is_boundary(by));
         self.move_front_forward(by);
         self
     }
 
     pub fn front_forward(
         &mut self,
-        pattern: impl IntoSearchable,
+        mut pattern: impl Pattern<H>,
         loc: PatternLoc,
-    ) -> Result<&mut Self, Failed> {
-        let by = find_directional_offset(
-            self.front_rem(),
-            pattern.into_searchable(),
-            loc,
-            Direction::Forward,
-        )
-        .ok_or(Failed)?;
+    ) -> Result<&mut Self, CursorError> {
+        let by =
+            find_directional_offset(self.front_rem(), pattern.by_ref(), loc, Direction::Forward)
+                .ok_or_else(|| self.failure(End::Front, Direction::Forward, loc, &pattern))?;
         self.move_front_forward(by);
         Ok(self)
     }
 
     pub fn front_forward_or(
         &mut self,
-        pattern: impl IntoSearchable,
+        pattern: impl Pattern<H>,
         loc: PatternLoc,
         fallback: FallBack,
     ) -> &mut Self {
@@ -300,37 +504,85 @@ impl<'a> ParseCursor<'a> {
 
     pub fn back_backward(
         &mut self,
-        pattern: impl IntoSearchable,
+        mut pattern: impl Pattern<H>,
         loc: PatternLoc,
-    ) -> Result<&mut Self, Failed> {
-        let by = find_directional_offset(
-            self.back_rem(),
-            pattern.into_searchable(),
-            loc,
-            Direction::Backward,
-        )
-        .ok_or(Failed)?;
+    ) -> Result<&mut Self, CursorError> {
+        let by =
+            find_directional_offset(self.back_rem(), pattern.by_ref(), loc, Direction::Backward)
+                .ok_or_else(|| self.failure(End::Back, Direction::Backward, loc, &pattern))?;
         self.move_back_backward(by);
         Ok(self)
     }
 
-    pub fn front_backward(
+    pub fn back_backward_by(&mut self, by: usize) -> &mut Self {
+        let view = self.back_rem();
+        assert!(by <= view.len() && view.is_boundary(view.len() - by));
+        self.move_back_backward(by);
+        self
+    }
+
+    pub fn back_backward_or(
         &mut self,
-        pattern: impl IntoSearchable,
+        pattern: impl Pattern<H>,
         loc: PatternLoc,
-        inward_strategy: InwardStrategy,
-    ) -> Result<&mut Self, Failed> {
-        let view = match inward_strategy {
+        fallback: FallBack,
+    ) -> &mut Self {
+        if self.back_backward(pattern, loc).is_err() {
+            match fallback {
+                FallBack::ToTheEnd => self.move_back_backward(self.back_rem().len()),
+                FallBack::StayAtBeginning => (),
+            }
+        }
+        self
+    }
+
+    fn front_backward_view(&self, inward_strategy: InwardStrategy) -> &'a H {
+        match inward_strategy {
             InwardStrategy::CursorOnly => self.cursor(),
             InwardStrategy::WholeData => self.all_but_front_rem(),
-        };
-        let by = find_directional_offset(view, pattern.into_searchable(), loc, Direction::Backward)
-            .ok_or(Failed)?;
+        }
+    }
+
+    pub fn front_backward(
+        &mut self,
+        mut pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+    ) -> Result<&mut Self, CursorError> {
+        let view = self.front_backward_view(inward_strategy);
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Backward)
+            .ok_or_else(|| self.failure(End::Front, Direction::Backward, loc, &pattern))?;
         self.move_front_backward(by, inward_strategy);
         Ok(self)
     }
 
-    fn back_forward_view(&self, inward_strategy: InwardStrategy) -> &str {
+    pub fn front_backward_by(&mut self, by: usize, inward_strategy: InwardStrategy) -> &mut Self {
+        let view = self.front_backward_view(inward_strategy);
+        assert!(by <= view.len() && view.is_boundary(view.len() - by));
+        self.move_front_backward(by, inward_strategy);
+        self
+    }
+
+    pub fn front_backward_or(
+        &mut self,
+        pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+        fallback: FallBack,
+    ) -> &mut Self {
+        if self.front_backward(pattern, loc, inward_strategy).is_err() {
+            match fallback {
+                FallBack::ToTheEnd => self.move_front_backward(
+                    self.front_backward_view(inward_strategy).len(),
+                    inward_strategy,
+                ),
+                FallBack::StayAtBeginning => (),
+            }
+        }
+        self
+    }
+
+    fn back_forward_view(&self, inward_strategy: InwardStrategy) -> &'a H {
         match inward_strategy {
             InwardStrategy::CursorOnly => self.cursor(),
             InwardStrategy::WholeData => self.all_but_back_rem(),
@@ -339,26 +591,26 @@ impl<'a> ParseCursor<'a> {
 
     pub fn back_forward(
         &mut self,
-        pattern: impl IntoSearchable,
+        mut pattern: impl Pattern<H>,
         loc: PatternLoc,
         inward_strategy: InwardStrategy,
-    ) -> Result<&mut Self, Failed> {
+    ) -> Result<&mut Self, CursorError> {
         let view = self.back_forward_view(inward_strategy);
-        let by = find_directional_offset(view, pattern.into_searchable(), loc, Direction::Forward)
-            .ok_or(Failed)?;
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Forward)
+            .ok_or_else(|| self.failure(End::Back, Direction::Forward, loc, &pattern))?;
         self.move_back_forward(by, inward_strategy);
         Ok(self)
     }
 
     pub fn back_forward_by(&mut self, by: usize, inward_strategy: InwardStrategy) -> &mut Self {
-        assert!(self.back_forward_view(inward_strategy).is_char_boundary(by));
+        assert!(self.back_forward_view(inward_strategy).is_boundary(by));
         self.move_back_forward(by, inward_strategy);
         self
     }
 
     pub fn back_forward_or(
         &mut self,
-        pattern: impl IntoSearchable,
+        pattern: impl Pattern<H>,
         loc: PatternLoc,
         inward_strategy: InwardStrategy,
         fallback: FallBack,
@@ -377,24 +629,132 @@ impl<'a> ParseCursor<'a> {
 
     pub fn step(
         &mut self,
-        mut f: impl FnMut(&mut Self) -> Result<&mut Self, Failed>,
-    ) -> Result<&'a str, Failed> {
+        mut f: impl FnMut(&mut Self) -> Result<&mut Self, CursorError>,
+    ) -> Result<&'a H, CursorError> {
         let mut candidate = self.clone();
         f(&mut candidate)?;
         *self = candidate;
         Ok(self.cursor())
     }
 
+    // `step`, adding `label` to the context of the error if `f` fails, so
+    // nested steps build up the stack of what was being parsed.
+    pub fn step_context(
+        &mut self,
+        label: impl Into<Cow<'static, str>>,
+        f: impl FnMut(&mut Self) -> Result<&mut Self, CursorError>,
+    ) -> Result<&'a H, CursorError> {
+        self.step(f).context(label)
+    }
+
     pub fn iter_steps(
         self,
-        mut f: impl FnMut(&mut Self) -> Result<&mut Self, Failed>,
-    ) -> impl Iterator<Item = &'a str> {
+        mut f: impl FnMut(&mut Self) -> Result<&mut Self, CursorError>,
+    ) -> impl Iterator<Item = &'a H> {
         let mut state = self;
         std::iter::from_fn(move || {
             state.back_to_front();
             state.step(&mut f).ok()
         })
     }
+
+    // Like `iter_steps`, but a step failing before the front end reached the
+    // end of the bounds is yielded as the last item instead of ending quietly.
+    pub fn try_iter_steps(
+        self,
+        mut f: impl FnMut(&mut Self) -> Result<&mut Self, CursorError>,
+    ) -> impl Iterator<Item = Result<&'a H, CursorError>> {
+        let mut state = Some(self);
+        std::iter::from_fn(move || {
+            let cursor = state.as_mut()?;
+            cursor.back_to_front();
+            match cursor.step(&mut f) {
+                Ok(item) => Some(Ok(item)),
+                Err(_) if cursor.cursor_range.end == cursor.bounds.end => None,
+                Err(err) => {
+                    state = None;
+                    Some(Err(err))
+                }
+            }
+        })
+    }
+
+    pub fn iter_steps_back(
+        self,
+        mut f: impl FnMut(&mut Self) -> Result<&mut Self, CursorError>,
+    ) -> impl Iterator<Item = &'a H> {
+        let mut state = self;
+        std::iter::from_fn(move || {
+            state.front_to_back();
+            state.step(&mut f).ok()
+        })
+    }
+
+    // `forward` walks from the front end of `self`, `backward` from the end of
+    // the bounds, and iteration stops once they would overlap.
+    pub fn iter_steps_double_ended<F, B>(
+        self,
+        forward: F,
+        backward: B,
+    ) -> DoubleEndedSteps<'a, H, F, B>
+    where
+        F: FnMut(&mut Self) -> Result<&mut Self, CursorError>,
+        B: FnMut(&mut Self) -> Result<&mut Self, CursorError>,
+    {
+        let bounds_end = self.bounds.end;
+        let mut back = self.clone();
+        back.cursor_range = bounds_end..bounds_end;
+        DoubleEndedSteps {
+            front: self,
+            back,
+            forward,
+            backward,
+        }
+    }
+}
+
+pub struct DoubleEndedSteps<'a, H: ?Sized, F, B> {
+    front: ParseCursor<'a, H>,
+    back: ParseCursor<'a, H>,
+    forward: F,
+    backward: B,
+}
+
+impl<'a, H, F, B> Iterator for DoubleEndedSteps<'a, H, F, B>
+where
+    H: ?Sized + Haystack,
+    F: for<'c> FnMut(&'c mut ParseCursor<'a, H>) -> Result<&'c mut ParseCursor<'a, H>, CursorError>,
+{
+    type Item = &'a H;
+
+    fn next(&mut self) -> Option<&'a H> {
+        let mut candidate = self.front.clone();
+        candidate.back_to_front();
+        (self.forward)(&mut candidate).ok()?;
+        if candidate.cursor_range.end > self.back.cursor_range.start {
+            return None;
+        }
+        self.front = candidate;
+        Some(self.front.cursor())
+    }
+}
+
+impl<'a, H, F, B> DoubleEndedIterator for DoubleEndedSteps<'a, H, F, B>
+where
+    H: ?Sized + Haystack,
+    F: for<'c> FnMut(&'c mut ParseCursor<'a, H>) -> Result<&'c mut ParseCursor<'a, H>, CursorError>,
+    B: for<'c> FnMut(&'c mut ParseCursor<'a, H>) -> Result<&'c mut ParseCursor<'a, H>, CursorError>,
+{
+    fn next_back(&mut self) -> Option<&'a H> {
+        let mut candidate = self.back.clone();
+        candidate.front_to_back();
+        (self.backward)(&mut candidate).ok()?;
+        if candidate.cursor_range.start < self.front.cursor_range.end {
+            return None;
+        }
+        self.back = candidate;
+        Some(self.back.cursor())
+    }
 }
 
 #[cfg(test)]
@@ -988,4 +1348,558 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn test_failed_move_reports_position_and_pattern() {
+        let mut cursor = ParseCursor::new_empty_start("ERROR: oops (at line 3)");
+        cursor
+            .back_forward(
+                "ERROR: ",
+                PatternLoc::BeginningOnce,
+                InwardStrategy::WholeData,
+            )
+            .unwrap();
+        let err = cursor
+            .front_forward(", column ", PatternLoc::FirstExcluded)
+            .unwrap_err();
+        cursor.check_invariants();
+        assert_eq!(err.offset(), 7);
+        assert_eq!(err.end(), Some(End::Front));
+        assert_eq!(err.direction(), Some(Direction::Forward));
+        assert_eq!(err.loc(), Some(PatternLoc::FirstExcluded));
+        assert_eq!(err.pattern(), "', column '");
+        assert_eq!(
+            err.to_string(),
+            "expected ', column ' (FirstExcluded moving front end forward at offset 7)"
+        );
+
+        let err = cursor
+            .back_backward('#', PatternLoc::FirstIncluded)
+            .unwrap_err();
+        assert_eq!(err.offset(), 7);
+        assert_eq!(err.end(), Some(End::Back));
+        assert_eq!(err.direction(), Some(Direction::Backward));
+        assert_eq!(err.pattern(), "'#'");
+    }
+
+    #[test]
+    fn test_move_details_only_on_move_errors() {
+        let mut cursor = ParseCursor::new_empty_start("one\ntwo");
+        let err = cursor.back_backward_lines(1).unwrap_err();
+        assert_eq!(
+            (err.end(), err.direction(), err.loc()),
+            (Some(End::Back), Some(Direction::Backward), None)
+        );
+        let err = CursorError::invalid(2, "a number", "not a digit");
+        assert_eq!((err.end(), err.direction(), err.loc()), (None, None, None));
+        let err = CursorError::from(Failed);
+        assert_eq!((err.end(), err.direction(), err.loc()), (None, None, None));
+    }
+
+    #[test]
+    fn test_unit_failed_still_usable() {
+        fn digits(c: &mut ParseCursor) -> Result<u32, Failed> {
+            c.front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningMany)?;
+            if c.cursor().is_empty() {
+                return Err(Failed);
+            }
+            c.cursor().parse().map_err(|_| Failed)
+        }
+        assert_eq!(
+            digits(&mut ParseCursor::new_empty_start("42x")).unwrap(),
+            42
+        );
+        assert!(matches!(
+            digits(&mut ParseCursor::new_empty_start("x")),
+            Err(Failed)
+        ));
+        match digits(&mut ParseCursor::new_empty_start("12345678901")) {
+            Err(Failed) => (),
+            Ok(_) => panic!("out of range number accepted"),
+        }
+
+        let converted =
+            || -> Result<u32, CursorError> { Ok(digits(&mut ParseCursor::new_empty_start(""))?) };
+        let err = converted().unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::Unlocated);
+        assert_eq!(err.to_string(), "failed");
+    }
+
+    #[test]
+    fn test_context_accumulates_through_steps() {
+        let mut cursor = ParseCursor::new_empty_start("(/etc/baz at line 0)");
+        let err = cursor
+            .step(|c| {
+                c.back_forward('(', PatternLoc::FirstIncluded, InwardStrategy::WholeData)?;
+                c.step(|c| {
+                    c.back_forward(
+                        "at line ",
+                        PatternLoc::FirstIncluded,
+                        InwardStrategy::WholeData,
+                    )?
+                    .front_forward(", column ", PatternLoc::FirstExcluded)
+                    .context("parsing column number")
+                })?;
+                Ok(c)
+            })
+            .context("parsing location inside first parenthesis")
+            .unwrap_err();
+        assert_eq!(cursor.cursor_range(), 0..0);
+        assert_eq!(
+            err.context().collect::<Vec<_>>(),
+            [
+                "parsing column number",
+                "parsing location inside first parenthesis"
+            ]
+        );
+        assert_eq!(
+            err.to_string(),
+            "expected ', column ' while parsing column number \
+             while parsing location inside first parenthesis \
+             (FirstExcluded moving front end forward at offset 18)"
+        );
+    }
+
+    #[test]
+    fn test_step_context_labels_nested_steps() {
+        let mut cursor = ParseCursor::new_empty_start("(/etc/baz at line 0)");
+        let err = cursor
+            .step_context("parsing location inside first parenthesis", |c| {
+                c.back_forward('(', PatternLoc::FirstIncluded, InwardStrategy::WholeData)?;
+                c.step_context("parsing column number", |c| {
+                    c.front_forward(", column ", PatternLoc::FirstExcluded)
+                })?;
+                Ok(c)
+            })
+            .unwrap_err();
+        assert_eq!(cursor.cursor_range(), 0..0);
+        assert_eq!(
+            err.context().collect::<Vec<_>>(),
+            [
+                "parsing column number",
+                "parsing location inside first parenthesis"
+            ]
+        );
+        let path = cursor
+            .step_context("parsing path", |c| {
+                c.back_forward('(', PatternLoc::FirstIncluded, InwardStrategy::WholeData)?
+                    .front_forward(' ', PatternLoc::FirstExcluded)
+            })
+            .unwrap();
+        assert_eq!(path, "/etc/baz");
+
+        let cursor = ParseCursor::new_empty_start("1,2,x,4");
+        let items: Vec<_> = cursor
+            .try_iter_steps(|c| {
+                c.front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningOnce)?
+                    .front_forward_or(',', PatternLoc::FirstIncluded, FallBack::ToTheEnd);
+                Ok(c)
+            })
+            .collect();
+        assert_eq!(items[..2], [Ok("1,"), Ok("2,")]);
+        assert_eq!(items[2].as_ref().unwrap_err().offset(), 4);
+        assert_eq!(items.len(), 3);
+        let cursor = ParseCursor::new_empty_start("1,2");
+        let mut items = cursor.try_iter_steps(|c| {
+            c.front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningOnce)?
+                .front_forward_or(',', PatternLoc::FirstIncluded, FallBack::ToTheEnd);
+            Ok(c)
+        });
+        assert_eq!(items.next(), Some(Ok("1,")));
+        assert_eq!(items.next(), Some(Ok("2")));
+        assert_eq!(items.next(), None);
+    }
+
+    #[test]
+    fn test_byte_cursor_over_invalid_utf8() {
+        let data: &[u8] = b"\xff\xfeERROR: bad (line 42)\xff";
+        let mut cursor = ByteCursor::new_empty_start(data);
+        cursor
+            .back_forward(
+                |b: u8| b.is_ascii(),
+                PatternLoc::FirstExcluded,
+                InwardStrategy::WholeData,
+            )
+            .unwrap()
+            .back_forward(
+                b"ERROR: ",
+                PatternLoc::BeginningOnce,
+                InwardStrategy::WholeData,
+            )
+            .unwrap()
+            .front_forward(b'(', PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), b"bad ");
+        cursor.back_to_front();
+        let line = cursor
+            .step(|c| {
+                c.back_forward(
+                    "line ",
+                    PatternLoc::FirstIncluded,
+                    InwardStrategy::WholeData,
+                )?
+                .front_forward(|b: u8| b.is_ascii_digit(), PatternLoc::BeginningMany)
+            })
+            .unwrap();
+        assert_eq!(line, b"42");
+        cursor.check_invariants();
+
+        let err = cursor
+            .front_forward(b"\x00end", PatternLoc::FirstIncluded)
+            .unwrap_err();
+        assert_eq!(err.pattern(), "b\"\\x00end\"");
+        assert_eq!(err.offset(), 21);
+    }
+
+    #[test]
+    fn test_find_directional_offset_bytes() {
+        assert_eq!(
+            find_directional_offset(
+                &b"abcabc"[..],
+                b"bc",
+                PatternLoc::EndOfLast,
+                Direction::Forward
+            ),
+            Some(6)
+        );
+        assert_eq!(
+            find_directional_offset(
+                &b"aaabbb"[..],
+                b'b',
+                PatternLoc::BeginningMany,
+                Direction::Backward
+            ),
+            Some(3)
+        );
+        assert_eq!(
+            find_directional_offset(
+                &b"hello world"[..],
+                b"o",
+                PatternLoc::FirstIncluded,
+                Direction::Backward
+            ),
+            Some(4)
+        );
+        assert_eq!(
+            find_directional_offset(
+                &b"  hi  "[..],
+                |b: u8| b == b' ',
+                PatternLoc::StartOfSuffixMany,
+                Direction::Forward
+            ),
+            Some(4)
+        );
+    }
+
+    #[test]
+    fn test_find_directional_offset_nth() {
+        let path = "a/b/c/d";
+        let find = |loc, direction| find_directional_offset(path, '/', loc, direction);
+        assert_eq!(
+            find(PatternLoc::NthExcluded(0), Direction::Forward),
+            Some(1)
+        );
+        assert_eq!(
+            find(PatternLoc::NthExcluded(2), Direction::Forward),
+            Some(5)
+        );
+        assert_eq!(
+            find(PatternLoc::NthIncluded(1), Direction::Forward),
+            Some(4)
+        );
+        assert_eq!(find(PatternLoc::NthExcluded(3), Direction::Forward), None);
+        assert_eq!(
+            find(PatternLoc::NthLastExcluded(1), Direction::Forward),
+            Some(3)
+        );
+        assert_eq!(
+            find(PatternLoc::NthLastIncluded(0), Direction::Forward),
+            Some(6)
+        );
+        assert_eq!(
+            find(PatternLoc::NthExcluded(1), Direction::Backward),
+            Some(3)
+        );
+        assert_eq!(
+            find(PatternLoc::NthIncluded(1), Direction::Backward),
+            Some(4)
+        );
+        assert_eq!(
+            find(PatternLoc::NthLastExcluded(0), Direction::Backward),
+            Some(5)
+        );
+        assert_eq!(
+            find(PatternLoc::NthLastIncluded(2), Direction::Backward),
+            Some(2)
+        );
+        assert_eq!(
+            find(PatternLoc::NthLastIncluded(3), Direction::Backward),
+            None
+        );
+        // Occurrences do not overlap, and empty ones are stepped over.
+        assert_eq!(
+            find_directional_offset(
+                "aaaaa",
+                "aa",
+                PatternLoc::NthExcluded(1),
+                Direction::Forward
+            ),
+            Some(2)
+        );
+        assert_eq!(
+            find_directional_offset(
+                "aaaaa",
+                "aa",
+                PatternLoc::NthLastExcluded(1),
+                Direction::Forward
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            find_directional_offset("éa", "", PatternLoc::NthExcluded(1), Direction::Forward),
+            Some(2)
+        );
+        assert_eq!(
+            find_directional_offset("aé", "", PatternLoc::NthExcluded(1), Direction::Backward),
+            Some(2)
+        );
+        // Running out of view after an empty match at its end.
+        assert_eq!(
+            find_directional_offset("ab", "", PatternLoc::NthExcluded(3), Direction::Forward),
+            None
+        );
+        assert_eq!(
+            find_directional_offset(
+                "ab",
+                "",
+                PatternLoc::NthLastIncluded(3),
+                Direction::Backward
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_nth_moves() {
+        let row = "id,name,city,zip,country";
+        let mut cursor = cursor_at(row, 0..0);
+        cursor
+            .back_forward(',', PatternLoc::NthIncluded(1), InwardStrategy::WholeData)
+            .unwrap()
+            .front_forward(',', PatternLoc::NthExcluded(0))
+            .unwrap();
+        assert_eq!(cursor.cursor(), "city");
+        let err = cursor
+            .front_forward(',', PatternLoc::NthExcluded(2))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected ',' (NthExcluded(2) moving front end forward at offset 12)"
+        );
+        assert_eq!(cursor.cursor(), "city");
+
+        let mut cursor = cursor_at(row, row.len()..row.len());
+        cursor
+            .back_backward(',', PatternLoc::NthExcluded(1))
+            .unwrap()
+            .front_backward(',', PatternLoc::NthIncluded(0), InwardStrategy::CursorOnly)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "zip");
+    }
+
+    fn cursor_at(data: &str, range: Range<usize>) -> ParseCursor<'_> {
+        ParseCursor::with_range(data, range)
+    }
+
+    #[test]
+    fn test_front_forward_family() {
+        let mut cursor = cursor_at("ab,cd,ef", 0..0);
+        cursor
+            .front_forward(',', PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "ab,");
+        cursor.front_forward_by(2);
+        assert_eq!(cursor.cursor(), "ab,cd");
+        cursor.front_forward_or(';', PatternLoc::FirstExcluded, FallBack::StayAtBeginning);
+        assert_eq!(cursor.cursor(), "ab,cd");
+        cursor.front_forward_or(';', PatternLoc::FirstExcluded, FallBack::ToTheEnd);
+        assert_eq!(cursor.cursor(), "ab,cd,ef");
+        cursor.check_invariants();
+    }
+
+    #[test]
+    fn test_back_backward_family() {
+        let mut cursor = cursor_at("ab,cd,ef", 8..8);
+        cursor
+            .back_backward(',', PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), ",ef");
+        cursor.back_backward_by(2);
+        assert_eq!(cursor.cursor(), "cd,ef");
+        cursor.back_backward_or(';', PatternLoc::FirstExcluded, FallBack::StayAtBeginning);
+        assert_eq!(cursor.cursor(), "cd,ef");
+        cursor.back_backward_or(';', PatternLoc::FirstExcluded, FallBack::ToTheEnd);
+        assert_eq!(cursor.cursor(), "ab,cd,ef");
+        cursor.check_invariants();
+    }
+
+    #[test]
+    fn test_front_backward_family() {
+        let mut cursor = cursor_at("ab,cd,ef", 0..8);
+        cursor
+            .front_backward(',', PatternLoc::FirstIncluded, InwardStrategy::CursorOnly)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "ab,cd");
+        cursor.front_backward_by(1, InwardStrategy::CursorOnly);
+        assert_eq!(cursor.cursor(), "ab,c");
+        cursor.front_backward_or(
+            ';',
+            PatternLoc::FirstExcluded,
+            InwardStrategy::CursorOnly,
+            FallBack::StayAtBeginning,
+        );
+        assert_eq!(cursor.cursor(), "ab,c");
+        cursor.front_backward_or(
+            ';',
+            PatternLoc::FirstExcluded,
+            InwardStrategy::CursorOnly,
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.cursor_range(), 0..0);
+        cursor.check_invariants();
+    }
+
+    #[test]
+    fn test_back_forward_family() {
+        let mut cursor = cursor_at("ab,cd,ef", 0..8);
+        cursor
+            .back_forward(',', PatternLoc::FirstIncluded, InwardStrategy::CursorOnly)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "cd,ef");
+        cursor.back_forward_by(1, InwardStrategy::CursorOnly);
+        assert_eq!(cursor.cursor(), "d,ef");
+        cursor.back_forward_or(
+            ';',
+            PatternLoc::FirstExcluded,
+            InwardStrategy::CursorOnly,
+            FallBack::StayAtBeginning,
+        );
+        assert_eq!(cursor.cursor(), "d,ef");
+        cursor.back_forward_or(
+            ';',
+            PatternLoc::FirstExcluded,
+            InwardStrategy::CursorOnly,
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.cursor_range(), 8..8);
+        cursor.check_invariants();
+    }
+
+    #[test]
+    fn test_inward_moves_crossing_the_other_end() {
+        let mut cursor = cursor_at("ab,cd,ef", 3..5);
+        cursor
+            .front_backward(',', PatternLoc::FirstIncluded, InwardStrategy::WholeData)
+            .unwrap();
+        assert_eq!(cursor.cursor_range(), 2..2);
+
+        let mut cursor = cursor_at("ab,cd,ef", 3..5);
+        cursor.front_backward_by(4, InwardStrategy::WholeData);
+        assert_eq!(cursor.cursor_range(), 1..1);
+
+        let mut cursor = cursor_at("ab,cd,ef", 3..5);
+        cursor
+            .back_forward(',', PatternLoc::FirstIncluded, InwardStrategy::WholeData)
+            .unwrap();
+        assert_eq!(cursor.cursor_range(), 6..6);
+
+        let mut cursor = cursor_at("ab,cd,ef", 3..5);
+        cursor.back_forward_or(
+            ';',
+            PatternLoc::FirstExcluded,
+            InwardStrategy::WholeData,
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.cursor_range(), 8..8);
+
+        let mut cursor = cursor_at("ab,cd,ef", 3..5);
+        cursor.front_backward_or(
+            ';',
+            PatternLoc::FirstExcluded,
+            InwardStrategy::WholeData,
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.cursor_range(), 0..0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_cursor_only_move_cannot_cross() {
+        let mut cursor = cursor_at("ab,cd,ef", 3..5);
+        cursor.front_backward_by(3, InwardStrategy::CursorOnly);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_backward_by_checks_boundaries() {
+        let mut cursor = cursor_at("aé", 3..3);
+        cursor.back_backward_by(1);
+    }
+
+    fn parenthesized(c: &mut ParseCursor<'_>) -> Result<(), CursorError> {
+        c.back_forward('(', PatternLoc::FirstIncluded, InwardStrategy::WholeData)?
+            .front_forward(')', PatternLoc::FirstExcluded)?;
+        Ok(())
+    }
+
+    fn parenthesized_back(c: &mut ParseCursor<'_>) -> Result<(), CursorError> {
+        c.front_backward(')', PatternLoc::FirstIncluded, InwardStrategy::WholeData)?
+            .back_backward('(', PatternLoc::FirstExcluded)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_iter_steps_back() {
+        let cursor = ParseCursor::new_empty_end("/usr/local/bin/ls");
+        let segments: Vec<_> = cursor
+            .iter_steps_back(|c| c.back_backward('/', PatternLoc::FirstIncluded))
+            .collect();
+        assert_eq!(segments, ["/ls", "/bin", "/local", "/usr"]);
+
+        let cursor = ParseCursor::new_empty_end("msg (a) (b) (c)");
+        let groups: Vec<_> = cursor
+            .iter_steps_back(|c| {
+                parenthesized_back(c)?;
+                Ok(c)
+            })
+            .take(2)
+            .collect();
+        assert_eq!(groups, ["c", "b"]);
+    }
+
+    #[test]
+    fn test_iter_steps_double_ended() {
+        let steps = || {
+            ParseCursor::new_empty_start("msg (a) (b) (c) (d)").iter_steps_double_ended(
+                |c| {
+                    parenthesized(c)?;
+                    Ok(c)
+                },
+                |c| {
+                    parenthesized_back(c)?;
+                    Ok(c)
+                },
+            )
+        };
+        assert_eq!(steps().collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+        assert_eq!(steps().rev().collect::<Vec<_>>(), ["d", "c", "b", "a"]);
+
+        let mut steps = steps();
+        assert_eq!(steps.next(), Some("a"));
+        assert_eq!(steps.next_back(), Some("d"));
+        assert_eq!(steps.next_back(), Some("c"));
+        assert_eq!(steps.next(), Some("b"));
+        assert_eq!(steps.next(), None);
+        assert_eq!(steps.next_back(), None);
+    }
 }
@@ -0,0 +1,291 @@
+use std::ops::Range;
+
+use crate::{CursorError, Direction, End, Haystack, InwardStrategy, ParseCursor};
+
+// Lines end at `\n`, and a `\r` right before it is part of the terminator. The
+// last line does not need one, but a terminator at the very end does not start
+// an empty line. Only what is within the bounds of the cursor is looked at.
+
+// Where the contents of the line holding `from` end, and where the next one
+// starts if there is a terminator before `limit`.
+fn line_end(bytes: &[u8], from: usize, limit: usize) -> (usize, Option<usize>) {
+    match bytes[from..limit].iter().position(|&b| b == b'\n') {
+        Some(i) => {
+            let newline = from + i;
+            let end = if newline > from && bytes[newline - 1] == b'\r' {
+                newline - 1
+            } else {
+                newline
+            };
+            (end, Some(newline + 1))
+        }
+        None => (limit, None),
+    }
+}
+
+impl<'a, H: ?Sized + Haystack> ParseCursor<'a, H> {
+    fn line_start(&self, floor: usize, offset: usize) -> usize {
+        let bytes = &self.data.as_bytes()[floor..offset];
+        bytes
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(floor, |i| floor + i + 1)
+    }
+
+    // The lines of the cursor itself, bounds included. A cursor ending right
+    // after a terminator does not reach into the next line, but an empty one
+    // there is at the start of that line, like `position` has it.
+    fn lines_range(&self) -> Range<usize> {
+        let bytes = self.data.as_bytes();
+        let Range { start, end } = self.cursor_range;
+        let (end, _) = if end > start && bytes[end - 1] == b'\n' {
+            line_end(bytes, self.line_start(self.bounds.start, end - 1), end)
+        } else {
+            line_end(bytes, end, self.bounds.end)
+        };
+        self.line_start(self.bounds.start, start)..end
+    }
+
+    // Selects the whole lines the cursor touches, without their terminators.
+    pub fn select_line(&mut self) -> &mut Self {
+        self.cursor_range = self.lines_range();
+        self
+    }
+
+    // Restricts the bounds to the lines the cursor touches, so that no move
+    // can leave them. Offsets stay relative to the whole `data`.
+    pub fn restrict_to_line(&mut self) -> &mut Self {
+        self.bounds = self.lines_range();
+        self
+    }
+
+    // The `n`-th line start after `from`, the end of the view `from..limit`
+    // counting as one when the last line has no terminator, or how many
+    // there were.
+    fn lines_after(&self, from: usize, n: usize, limit: usize) -> Result<usize, usize> {
+        let bytes = self.data.as_bytes();
+        let mut pos = from;
+        for done in 0..n {
+            pos = match line_end(bytes, pos, limit) {
+                (_, Some(next)) => next,
+                (end, None) if end > pos => end,
+                _ => return Err(done),
+            };
+        }
+        Ok(pos)
+    }
+
+    // The `n`-th line start before `from`, within `floor..from`, or how many
+    // there were.
+    fn lines_before(&self, from: usize, n: usize, floor: usize) -> Result<usize, usize> {
+        let mut pos = from;
+        for done in 0..n {
+            if pos == floor {
+                return Err(done);
+            }
+            pos = self.line_start(floor, pos - 1);
+        }
+        Ok(pos)
+    }
+
+    // Moves the front end to the `n`-th line start after it. The end of the
+    // bounds counts as one when the last line has no terminator.
+    pub fn front_forward_lines(&mut self, n: usize) -> Result<&mut Self, CursorError> {
+        let from = self.cursor_range.end;
+        let pos = self
+            .lines_after(from, n, self.bounds.end)
+            .map_err(|done| self.too_few_lines(End::Front, Direction::Forward, n, done))?;
+        self.move_front_forward(pos - from);
+        Ok(self)
+    }
+
+    // Moves the back end to the `n`-th line start before it, so moving by one
+    // goes to the start of the current line unless already there.
+    pub fn back_backward_lines(&mut self, n: usize) -> Result<&mut Self, CursorError> {
+        let from = self.cursor_range.start;
+        let pos = self
+            .lines_before(from, n, self.bounds.start)
+            .map_err(|done| self.too_few_lines(End::Back, Direction::Backward, n, done))?;
+        self.move_back_backward(from - pos);
+        Ok(self)
+    }
+
+    // Like `front_forward_lines`, for the back end, within the cursor or
+    // the rest of the bounds.
+    pub fn back_forward_lines(
+        &mut self,
+        n: usize,
+        inward_strategy: InwardStrategy,
+    ) -> Result<&mut Self, CursorError> {
+        let from = self.cursor_range.start;
+        let limit = from + self.back_forward_view(inward_strategy).len();
+        let pos = self
+            .lines_after(from, n, limit)
+            .map_err(|done| self.too_few_lines(End::Back, Direction::Forward, n, done))?;
+        self.move_back_forward(pos - from, inward_strategy);
+        Ok(self)
+    }
+
+    // Like `back_backward_lines`, for the front end, within the cursor or
+    // the rest of the bounds.
+    pub fn front_backward_lines(
+        &mut self,
+        n: usize,
+        inward_strategy: InwardStrategy,
+    ) -> Result<&mut Self, CursorError> {
+        let from = self.cursor_range.end;
+        let floor = from - self.front_backward_view(inward_strategy).len();
+        let pos = self
+            .lines_before(from, n, floor)
+            .map_err(|done| self.too_few_lines(End::Front, Direction::Backward, n, done))?;
+        self.move_front_backward(from - pos, inward_strategy);
+        Ok(self)
+    }
+
+    fn too_few_lines(
+        &self,
+        end: End,
+        direction: Direction,
+        wanted: usize,
+        found: usize,
+    ) -> CursorError {
+        let offset = match end {
+            End::Front => self.cursor_range.end,
+            End::Back => self.cursor_range.start,
+        };
+        CursorError::rejected(
+            offset,
+            end,
+            direction,
+            None,
+            format!("{wanted} more lines"),
+            format!("only {found} left"),
+        )
+    }
+
+    // The lines of the cursor as cursors each covering and restricted to one
+    // line, terminator excluded.
+    pub fn lines(&self) -> impl Iterator<Item = Self> + use<'a, '_, H> {
+        let mut pos = Some(self.cursor_range.start);
+        std::iter::from_fn(move || {
+            let from = pos.filter(|&from| from < self.cursor_range.end)?;
+            let (end, next) = line_end(self.data.as_bytes(), from, self.cursor_range.end);
+            pos = next;
+            let mut line = self.clone();
+            line.cursor_range = from..end;
+            line.bounds = from..end;
+            Some(line)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FallBack, PatternLoc};
+
+    #[test]
+    fn test_lines_and_terminators() {
+        let data = "first\r\nsecond\n\nlast";
+        let cursor = ParseCursor::new_full(data);
+        let lines: Vec<_> = cursor.lines().map(|line| line.cursor()).collect();
+        assert_eq!(lines, ["first", "second", "", "last"]);
+        let offsets: Vec<_> = cursor
+            .lines()
+            .map(|line| line.position(line.cursor_range.start).line)
+            .collect();
+        assert_eq!(offsets, [1, 2, 3, 4]);
+
+        let with_newline = ParseCursor::new_full("a\r\nb\n");
+        assert_eq!(
+            with_newline.lines().map(|l| l.cursor()).collect::<Vec<_>>(),
+            ["a", "b"]
+        );
+        assert_eq!(ParseCursor::new_full("").lines().count(), 0);
+    }
+
+    #[test]
+    fn test_move_by_lines() {
+        let data = "one\ntwo\r\nthree";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor.front_forward_lines(1).unwrap();
+        assert_eq!(cursor.front_rem(), "two\r\nthree");
+        cursor.front_forward_lines(2).unwrap();
+        assert!(cursor.front_rem().is_empty());
+        let err = cursor.front_forward_lines(1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected 1 more lines (only 0 left at offset 14)"
+        );
+
+        cursor.back_to_front();
+        cursor.back_backward_lines(1).unwrap();
+        assert_eq!(cursor.cursor(), "three");
+        cursor.back_backward_lines(2).unwrap();
+        assert_eq!(cursor.cursor(), data);
+        assert!(cursor.back_backward_lines(1).is_err());
+        assert_eq!(cursor.cursor(), data);
+
+        cursor
+            .back_forward_lines(1, InwardStrategy::CursorOnly)
+            .unwrap()
+            .front_backward_lines(1, InwardStrategy::CursorOnly)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "two\r\n");
+        cursor
+            .back_forward_lines(2, InwardStrategy::WholeData)
+            .unwrap();
+        assert_eq!(
+            (cursor.cursor_range.clone(), cursor.front_rem()),
+            (14..14, "")
+        );
+        assert!(
+            cursor
+                .back_forward_lines(1, InwardStrategy::WholeData)
+                .is_err()
+        );
+        cursor
+            .front_backward_lines(3, InwardStrategy::WholeData)
+            .unwrap();
+        assert_eq!(
+            (cursor.cursor_range.clone(), cursor.front_rem()),
+            (0..0, data)
+        );
+    }
+
+    #[test]
+    fn test_select_and_restrict_to_line() {
+        let data = "key: (open\nnext) line\n";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward('(', PatternLoc::FirstIncluded)
+            .unwrap();
+        cursor.back_to_front();
+        let mut line = cursor.clone();
+        line.select_line();
+        assert_eq!(line.cursor(), "key: (open");
+        // Ending right after a terminator stays on that line, but an empty
+        // cursor there is at the start of the next one.
+        let mut line = ParseCursor::new_empty_start(data);
+        line.front_forward_lines(1).unwrap();
+        let mut after = line.clone();
+        after.back_to_front();
+        line.select_line();
+        assert_eq!(line.cursor(), "key: (open");
+        let mut line = after;
+        line.select_line();
+        assert_eq!(line.cursor(), "next) line");
+
+        cursor.restrict_to_line();
+        assert!(
+            cursor
+                .front_forward(')', PatternLoc::FirstExcluded)
+                .is_err()
+        );
+        cursor.front_forward_or(')', PatternLoc::FirstExcluded, FallBack::ToTheEnd);
+        assert_eq!(cursor.cursor(), "open");
+        assert!(cursor.front_rem().is_empty());
+        assert_eq!(cursor.cursor_range, 6..10);
+        assert_eq!(cursor.position(6).column, 7);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::{
+    CursorError, Direction, Haystack, InwardStrategy, ParseCursor, Pattern, PatternLoc,
+    find_directional_offset,
+};
+
+// The `peek_*` methods return the offset into `data` the matching move would
+// put its end at, without moving. Moves that cross the other end would also
+// drag it along.
+impl<'a, H: ?Sized + Haystack> ParseCursor<'a, H> {
+    pub fn peek_front_forward(&self, pattern: impl Pattern<H>, loc: PatternLoc) -> Option<usize> {
+        find_directional_offset(self.front_rem(), pattern, loc, Direction::Forward)
+            .map(|by| self.cursor_range.end + by)
+    }
+
+    pub fn peek_back_backward(&self, pattern: impl Pattern<H>, loc: PatternLoc) -> Option<usize> {
+        find_directional_offset(self.back_rem(), pattern, loc, Direction::Backward)
+            .map(|by| self.cursor_range.start - by)
+    }
+
+    pub fn peek_front_backward(
+        &self,
+        pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+    ) -> Option<usize> {
+        let view = self.front_backward_view(inward_strategy);
+        find_directional_offset(view, pattern, loc, Direction::Backward)
+            .map(|by| self.cursor_range.end - by)
+    }
+
+    pub fn peek_back_forward(
+        &self,
+        pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+    ) -> Option<usize> {
+        let view = self.back_forward_view(inward_strategy);
+        find_directional_offset(view, pattern, loc, Direction::Forward)
+            .map(|by| self.cursor_range.start + by)
+    }
+
+    pub fn ensure_front_at_end(&mut self) -> Result<&mut Self, CursorError> {
+        if !self.front_rem().is_empty() {
+            return Err(CursorError::invalid(
+                self.cursor_range.end,
+                "end of data",
+                "front end stopped short",
+            ));
+        }
+        Ok(self)
+    }
+
+    pub fn ensure_back_at_start(&mut self) -> Result<&mut Self, CursorError> {
+        if !self.back_rem().is_empty() {
+            return Err(CursorError::invalid(
+                self.cursor_range.start,
+                "start of data",
+                "back end stopped short",
+            ));
+        }
+        Ok(self)
+    }
+
+    pub fn ensure_empty(&mut self) -> Result<&mut Self, CursorError> {
+        if !self.cursor().is_empty() {
+            return Err(CursorError::invalid(
+                self.cursor_range.start,
+                "an empty cursor",
+                format!("cursor holds {} bytes", self.cursor().len()),
+            ));
+        }
+        Ok(self)
+    }
+
+    // Checks that a single match of `pattern` spans the whole cursor.
+    pub fn ensure_matches(
+        &mut self,
+        mut pattern: impl Pattern<H>,
+    ) -> Result<&mut Self, CursorError> {
+        let cursor = self.cursor();
+        if pattern.prefix_len(cursor) != Some(cursor.len()) {
+            return Err(CursorError::invalid(
+                self.cursor_range.start,
+                pattern.describe(),
+                "cursor does not match entirely",
+            ));
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_peeks_do_not_move() {
+        let data = "ERROR: bad (thing)\nnext (line)";
+        let cursor = ParseCursor::new_empty_start(data);
+        assert_eq!(
+            cursor.peek_front_forward("ERROR:", PatternLoc::BeginningOnce),
+            Some(6)
+        );
+        let newline = cursor.peek_front_forward('\n', PatternLoc::FirstExcluded);
+        let paren = cursor.peek_front_forward('(', PatternLoc::FirstExcluded);
+        assert!(paren < newline);
+        assert_eq!(
+            cursor.peek_back_forward(')', PatternLoc::EndOfLast, InwardStrategy::WholeData),
+            Some(data.len())
+        );
+        assert_eq!(cursor.front_rem(), data);
+
+        let cursor = ParseCursor::new_empty_end(data);
+        assert_eq!(
+            cursor.peek_back_backward('\n', PatternLoc::FirstIncluded),
+            Some(18)
+        );
+        assert_eq!(
+            cursor.peek_front_backward('(', PatternLoc::FirstExcluded, InwardStrategy::WholeData),
+            Some(25)
+        );
+        assert_eq!(
+            cursor.peek_front_backward('(', PatternLoc::FirstExcluded, InwardStrategy::CursorOnly),
+            None
+        );
+    }
+
+    #[test]
+    fn test_ensure_in_steps() {
+        let mut cursor = ParseCursor::new_empty_start("1234");
+        let digits = cursor.step(|c| {
+            c.front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningMany)?
+                .ensure_front_at_end()?
+                .ensure_back_at_start()?
+                .ensure_matches("1234")
+        });
+        assert_eq!(digits, Ok("1234"));
+        assert!(cursor.ensure_empty().is_err());
+        cursor.back_to_front();
+        assert!(cursor.ensure_empty().is_ok());
+
+        let mut cursor = ParseCursor::new_full("12a");
+        let err = cursor.ensure_matches("12").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected '12' (cursor does not match entirely at offset 0)"
+        );
+        cursor.front_to_back();
+        assert!(cursor.ensure_front_at_end().is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use crate::{Haystack, ParseCursor};
+
+impl<H: ?Sized + Haystack> ParseCursor<'_, H> {
+    // Like `snap`, but the new cursor keeps the same `data`, only restricted
+    // to the cursor, so offsets and positions stay those of the whole input.
+    pub fn nested(&self) -> Self {
+        let mut nested = self.clone();
+        nested.bounds = self.cursor_range();
+        nested
+    }
+
+    // Moves both ends to those of `nested`, which has to be over the same
+    // data and within the bounds.
+    pub fn merge(&mut self, nested: &Self) -> &mut Self {
+        assert!(std::ptr::eq(self.data, nested.data));
+        let range = nested.cursor_range();
+        assert!(self.bounds.start <= range.start && range.end <= self.bounds.end);
+        self.cursor_range = range;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FallBack, InwardStrategy, PatternLoc};
+
+    #[test]
+    fn test_nested_parse_reports_absolute_offsets() {
+        let data = "ERROR: bad (at line x) (hint)";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .back_forward('(', PatternLoc::FirstIncluded, InwardStrategy::WholeData)
+            .unwrap()
+            .front_forward(')', PatternLoc::FirstExcluded)
+            .unwrap();
+        let mut inner = cursor.nested();
+        assert_eq!(inner.cursor(), "at line x");
+        inner.back_to_front();
+        inner.back_backward(' ', PatternLoc::FirstExcluded).unwrap();
+        assert_eq!(inner.back_rem(), "at line ");
+        let err = inner.parse::<u32>().unwrap_err();
+        assert_eq!(err.offset(), 20);
+
+        // Moves stop at the parent's cursor instead of running into "(hint)".
+        inner.front_forward_or('(', PatternLoc::FirstExcluded, FallBack::ToTheEnd);
+        assert_eq!(inner.cursor(), "x");
+        assert!(inner.front_rem().is_empty());
+        cursor.merge(&inner);
+        assert_eq!(cursor.front_rem(), ") (hint)");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_merge_checks_data() {
+        let mut cursor = ParseCursor::new_full("same text");
+        let other = String::from("same text");
+        cursor.merge(&ParseCursor::new_full(other.as_str()));
+    }
+}
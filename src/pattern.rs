@@ -0,0 +1,345 @@
+use std::ops::Range;
+
+use stable_string_patterns_method::{Searchable, StrPatternExt, WhiteSpace};
+
+use crate::{Haystack, PatternLoc};
+
+// The primitive searches `find_directional_offset` builds every `PatternLoc`
+// from. They work through `&mut self` so the pattern is still around to be
+// described when a move fails.
+pub trait Pattern<H: ?Sized + Haystack = str> {
+    fn find_in(&mut self, haystack: &H) -> Option<Range<usize>>;
+
+    fn rfind_in(&mut self, haystack: &H) -> Option<Range<usize>>;
+
+    // Length of the match anchored at the start of `haystack`.
+    fn prefix_len(&mut self, haystack: &H) -> Option<usize>;
+
+    // Length of the match anchored at the end of `haystack`.
+    fn suffix_len(&mut self, haystack: &H) -> Option<usize>;
+
+    fn describe(&self) -> String;
+
+    // The most bytes a match can span, when that is bounded. Streams use it
+    // to give up on a match at the start without reading further.
+    fn max_len(&self) -> Option<usize> {
+        None
+    }
+
+    // Whether searching at `loc` means anything for the pattern. Moves to
+    // an unsupported location fail without searching.
+    fn supports(&self, loc: PatternLoc) -> bool {
+        let _ = loc;
+        true
+    }
+
+    fn by_ref(&mut self) -> ByRef<'_, Self> {
+        ByRef(self)
+    }
+}
+
+pub struct ByRef<'p, P: ?Sized>(&'p mut P);
+
+impl<H: ?Sized + Haystack, P: ?Sized + Pattern<H>> Pattern<H> for ByRef<'_, P> {
+    fn find_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        self.0.find_in(haystack)
+    }
+
+    fn rfind_in(&mut self, haystack: &H) -> Option<Range<usize>> {
+        self.0.rfind_in(haystack)
+    }
+
+    fn prefix_len(&mut self, haystack: &H) -> Option<usize> {
+        self.0.prefix_len(haystack)
+    }
+
+    fn suffix_len(&mut self, haystack: &H) -> Option<usize> {
+        self.0.suffix_len(haystack)
+    }
+
+    fn describe(&self) -> String {
+        self.0.describe()
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        self.0.max_len()
+    }
+
+    fn supports(&self, loc: PatternLoc) -> bool {
+        self.0.supports(loc)
+    }
+}
+
+fn searchable_find(haystack: &str, pattern: impl Searchable) -> Option<Range<usize>> {
+    haystack
+        .match_indices_(pattern)
+        .next()
+        .map(|(offset, sub)| offset..offset + sub.len())
+}
+
+fn searchable_rfind(haystack: &str, pattern: impl Searchable) -> Option<Range<usize>> {
+    haystack
+        .rmatch_indices_(pattern)
+        .next()
+        .map(|(offset, sub)| offset..offset + sub.len())
+}
+
+fn searchable_prefix_len(haystack: &str, pattern: impl Searchable) -> Option<usize> {
+    let rem = haystack.strip_prefix_(pattern)?;
+    Some(haystack.len() - rem.len())
+}
+
+fn searchable_suffix_len(haystack: &str, pattern: impl Searchable) -> Option<usize> {
+    let rem = haystack.strip_suffix_(pattern)?;
+    Some(haystack.len() - rem.len())
+}
+
+// Mirrors the implementors of `Searchable`, which is sealed.
+macro_rules! impl_searchable_pattern {
+    (
+        [$($generics:tt)*] $ty:ty,
+        |$this:ident| $searchable:expr,
+        |$d:ident| $describe:expr,
+        |$m:ident| $max_len:expr
+    ) => {
+        impl<$($generics)*> Pattern<str> for $ty {
+            fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+                let $this = self;
+                searchable_find(haystack, $searchable)
+            }
+
+            fn rfind_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+                let $this = self;
+                searchable_rfind(haystack, $searchable)
+            }
+
+            fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+                let $this = self;
+                searchable_prefix_len(haystack, $searchable)
+            }
+
+            fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+                let $this = self;
+                searchable_suffix_len(haystack, $searchable)
+            }
+
+            fn describe(&self) -> String {
+                let $d = self;
+                $describe
+            }
+
+            fn max_len(&self) -> Option<usize> {
+                let $m = self;
+                Some($max_len)
+            }
+        }
+    };
+}
+
+fn describe_chars(chars: &[char]) -> String {
+    let alternatives: Vec<String> = chars
+        .iter()
+        .map(|c| format!("'{}'", c.escape_debug()))
+        .collect();
+    format!("one of [{}]", alternatives.join(", "))
+}
+
+// Patterns matching a single char span at most as many bytes as the widest.
+const CHAR_MAX_LEN: usize = char::MAX.len_utf8();
+
+impl_searchable_pattern!(
+    [] & str,
+    |this| *this,
+    |this| format!("'{}'", this.escape_debug()),
+    |this| this.len()
+);
+impl_searchable_pattern!(
+    [] char,
+    |this| *this,
+    |this| format!("'{}'", this.escape_debug()),
+    |this| this.len_utf8()
+);
+impl_searchable_pattern!(
+    [] & [char],
+    |this| *this,
+    |this| describe_chars(this),
+    |_this| CHAR_MAX_LEN
+);
+impl_searchable_pattern!(
+    [const N: usize] [char; N],
+    |this| *this,
+    |this| describe_chars(this),
+    |_this| CHAR_MAX_LEN
+);
+impl_searchable_pattern!(
+    [const N: usize] &[char; N],
+    |this| *this,
+    |this| describe_chars(*this),
+    |_this| CHAR_MAX_LEN
+);
+impl_searchable_pattern!(
+    [] WhiteSpace,
+    |_this| char::is_whitespace,
+    |_this| "whitespace".to_owned(),
+    |_this| CHAR_MAX_LEN
+);
+
+impl<F> Pattern<str> for F
+where
+    F: FnMut(char) -> bool,
+{
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        searchable_find(haystack, &mut *self)
+    }
+
+    fn rfind_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        searchable_rfind(haystack, &mut *self)
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        searchable_prefix_len(haystack, &mut *self)
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        searchable_suffix_len(haystack, &mut *self)
+    }
+
+    fn describe(&self) -> String {
+        "a character matching the predicate".to_owned()
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(CHAR_MAX_LEN)
+    }
+}
+
+// Literal patterns working on both haystacks, such as `AnyOf`, take literals
+// bounded by `AsRef<H> + AsRef<[u8]>`: they are searched as bytes, while
+// `AsRef<H>` keeps literals made of bytes from matching inside a `str`, where
+// they could split a char. Such literals are described as text when they are
+// valid UTF-8.
+pub(crate) fn describe_literal(literal: &[u8]) -> String {
+    match std::str::from_utf8(literal) {
+        Ok(text) => format!("'{}'", text.escape_debug()),
+        Err(_) => format!("b\"{}\"", literal.escape_ascii()),
+    }
+}
+
+fn bytes_find(haystack: &[u8], needle: &[u8]) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return Some(0..0);
+    }
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|offset| offset..offset + needle.len())
+}
+
+fn bytes_rfind(haystack: &[u8], needle: &[u8]) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return Some(haystack.len()..haystack.len());
+    }
+    haystack
+        .windows(needle.len())
+        .rposition(|window| window == needle)
+        .map(|offset| offset..offset + needle.len())
+}
+
+macro_rules! impl_byte_string_pattern {
+    ([$($generics:tt)*] $ty:ty) => {
+        impl<$($generics)*> Pattern<[u8]> for $ty {
+            fn find_in(&mut self, haystack: &[u8]) -> Option<Range<usize>> {
+                let needle: &[u8] = self.as_ref();
+                bytes_find(haystack, needle)
+            }
+
+            fn rfind_in(&mut self, haystack: &[u8]) -> Option<Range<usize>> {
+                let needle: &[u8] = self.as_ref();
+                bytes_rfind(haystack, needle)
+            }
+
+            fn prefix_len(&mut self, haystack: &[u8]) -> Option<usize> {
+                let needle: &[u8] = self.as_ref();
+                haystack.starts_with(needle).then_some(needle.len())
+            }
+
+            fn suffix_len(&mut self, haystack: &[u8]) -> Option<usize> {
+                let needle: &[u8] = self.as_ref();
+                haystack.ends_with(needle).then_some(needle.len())
+            }
+
+            fn describe(&self) -> String {
+                let needle: &[u8] = self.as_ref();
+                format!("b\"{}\"", needle.escape_ascii())
+            }
+
+            fn max_len(&self) -> Option<usize> {
+                let needle: &[u8] = self.as_ref();
+                Some(needle.len())
+            }
+        }
+    };
+}
+
+impl_byte_string_pattern!([] & [u8]);
+impl_byte_string_pattern!([const N: usize] &[u8; N]);
+impl_byte_string_pattern!([] & str);
+
+impl Pattern<[u8]> for u8 {
+    fn find_in(&mut self, haystack: &[u8]) -> Option<Range<usize>> {
+        bytes_find(haystack, &[*self])
+    }
+
+    fn rfind_in(&mut self, haystack: &[u8]) -> Option<Range<usize>> {
+        bytes_rfind(haystack, &[*self])
+    }
+
+    fn prefix_len(&mut self, haystack: &[u8]) -> Option<usize> {
+        (haystack.first() == Some(&*self)).then_some(1)
+    }
+
+    fn suffix_len(&mut self, haystack: &[u8]) -> Option<usize> {
+        (haystack.last() == Some(&*self)).then_some(1)
+    }
+
+    fn describe(&self) -> String {
+        format!("b'{}'", self.escape_ascii())
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(1)
+    }
+}
+
+impl<F> Pattern<[u8]> for F
+where
+    F: FnMut(u8) -> bool,
+{
+    fn find_in(&mut self, haystack: &[u8]) -> Option<Range<usize>> {
+        let offset = haystack.iter().position(|&b| self(b))?;
+        Some(offset..offset + 1)
+    }
+
+    fn rfind_in(&mut self, haystack: &[u8]) -> Option<Range<usize>> {
+        let offset = haystack.iter().rposition(|&b| self(b))?;
+        Some(offset..offset + 1)
+    }
+
+    fn prefix_len(&mut self, haystack: &[u8]) -> Option<usize> {
+        let &first = haystack.first()?;
+        self(first).then_some(1)
+    }
+
+    fn suffix_len(&mut self, haystack: &[u8]) -> Option<usize> {
+        let &last = haystack.last()?;
+        self(last).then_some(1)
+    }
+
+    fn describe(&self) -> String {
+        "a byte matching the predicate".to_owned()
+    }
+
+    fn max_len(&self) -> Option<usize> {
+        Some(1)
+    }
+}
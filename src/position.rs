@@ -0,0 +1,148 @@
+use std::fmt;
+
+use crate::{Haystack, ParseCursor};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LineIndex {
+    line_starts: Vec<usize>,
+}
+
+// All fields are 1-based except `offset`, which is the byte offset into `data`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub byte_column: usize,
+    pub utf16_column: usize,
+}
+
+impl LineIndex {
+    pub fn new<H: ?Sized + Haystack>(data: &H) -> Self {
+        let newlines = data
+            .as_bytes()
+            .iter()
+            .enumerate()
+            .filter(|&(_, &b)| b == b'\n')
+            .map(|(i, _)| i + 1);
+        Self {
+            line_starts: std::iter::once(0).chain(newlines).collect(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.line_starts.len()
+    }
+
+    pub fn position<H: ?Sized + Haystack>(&self, data: &H, offset: usize) -> Position {
+        assert!(data.is_boundary(offset));
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let (chars, utf16) = count_units(&data.as_bytes()[line_start..offset]);
+        Position {
+            offset,
+            line: line + 1,
+            column: chars + 1,
+            byte_column: offset - line_start + 1,
+            utf16_column: utf16 + 1,
+        }
+    }
+}
+
+fn count_units(bytes: &[u8]) -> (usize, usize) {
+    let mut chars = 0;
+    let mut utf16 = 0;
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            chars += 1;
+            utf16 += c.len_utf16();
+        }
+        // Invalid sequences count as the replacement character they display as.
+        if !chunk.invalid().is_empty() {
+            chars += 1;
+            utf16 += 1;
+        }
+    }
+    (chars, utf16)
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+impl<H: ?Sized + Haystack> ParseCursor<'_, H> {
+    pub fn line_index(&self) -> &LineIndex {
+        self.line_index.get_or_init(|| LineIndex::new(self.data))
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        self.line_index().position(self.data, offset)
+    }
+
+    pub fn back_position(&self) -> Position {
+        self.position(self.cursor_range.start)
+    }
+
+    pub fn front_position(&self) -> Position {
+        self.position(self.cursor_range.end)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{ByteCursor, InwardStrategy, PatternLoc};
+
+    #[test]
+    fn test_positions_of_both_ends() {
+        let mut cursor = ParseCursor::new_empty_start("first\nsecond line\r\nthird");
+        cursor
+            .back_forward("line", PatternLoc::FirstExcluded, InwardStrategy::WholeData)
+            .unwrap()
+            .front_forward("ird", PatternLoc::FirstExcluded)
+            .unwrap();
+        let back = cursor.back_position();
+        assert_eq!((back.offset, back.line, back.column), (13, 2, 8));
+        assert_eq!(back.to_string(), "2:8");
+        let front = cursor.front_position();
+        assert_eq!((front.offset, front.line, front.column), (21, 3, 3));
+        assert_eq!(cursor.line_index().line_count(), 3);
+        assert_eq!(cursor.position(5).line, 1);
+        assert_eq!(cursor.position(6).line, 2);
+    }
+
+    #[test]
+    fn test_columns_in_chars_bytes_and_utf16() {
+        let cursor = ParseCursor::new_full("é𝄞x");
+        let position = cursor.front_position();
+        assert_eq!(position.column, 4);
+        assert_eq!(position.byte_column, 8);
+        assert_eq!(position.utf16_column, 5);
+    }
+
+    #[test]
+    fn test_bytes_count_invalid_sequences_as_one_char() {
+        let cursor = ByteCursor::new_full(b"\n\xffa");
+        let position = cursor.front_position();
+        assert_eq!(
+            (position.line, position.column, position.byte_column),
+            (2, 3, 3)
+        );
+    }
+
+    #[test]
+    fn test_index_is_built_lazily_and_shared_between_clones() {
+        let cursor = ParseCursor::new_full("a\nb");
+        let early = cursor.clone();
+        let nested = cursor.nested();
+        assert!(early.line_index.get().is_none());
+        cursor.front_position();
+        assert!(Arc::ptr_eq(&cursor.line_index, &early.line_index));
+        assert!(Arc::ptr_eq(&cursor.line_index, &nested.line_index));
+        assert_eq!(early.line_index.get().unwrap().line_count(), 2);
+    }
+}
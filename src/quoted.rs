@@ -0,0 +1,199 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use crate::{CursorError, ParseCursor, Pattern, PatternLoc};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Escape {
+    // `\` escapes the next character, as in C or JSON.
+    Backslash,
+    // The quote is written twice, as in CSV or SQL.
+    Doubled,
+    // Nothing can be escaped, the first quote closes.
+    Raw,
+}
+
+// Matches a whole quoted literal, quotes included. An unterminated literal
+// does not match.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Quoted {
+    quote: char,
+    escape: Escape,
+}
+
+impl Quoted {
+    pub fn new(quote: char, escape: Escape) -> Self {
+        Self { quote, escape }
+    }
+
+    pub fn double() -> Self {
+        Self::new('"', Escape::Backslash)
+    }
+
+    pub fn single() -> Self {
+        Self::new('\'', Escape::Backslash)
+    }
+
+    // Offset of the closing quote in the contents following an opening one.
+    fn closing(&self, contents: &str) -> Option<usize> {
+        let mut chars = contents.char_indices();
+        while let Some((i, c)) = chars.next() {
+            if self.escape == Escape::Backslash && c == '\\' {
+                chars.next();
+            } else if c == self.quote {
+                let doubled = self.escape == Escape::Doubled
+                    && contents[i + c.len_utf8()..].starts_with(self.quote);
+                if !doubled {
+                    return Some(i);
+                }
+                chars.next();
+            }
+        }
+        None
+    }
+
+    // Resolves the escapes of the contents of a literal, quotes excluded.
+    // Borrows `contents` when there is nothing to resolve.
+    pub fn unescape<'a>(&self, contents: &'a str) -> Cow<'a, str> {
+        let escaped = match self.escape {
+            Escape::Backslash => '\\',
+            Escape::Doubled => self.quote,
+            Escape::Raw => return Cow::Borrowed(contents),
+        };
+        if !contents.contains(escaped) {
+            return Cow::Borrowed(contents);
+        }
+        let mut unescaped = String::with_capacity(contents.len());
+        let mut chars = contents.chars();
+        while let Some(c) = chars.next() {
+            if c != escaped {
+                unescaped.push(c);
+                continue;
+            }
+            match (self.escape, chars.next()) {
+                (Escape::Backslash, Some('n')) => unescaped.push('\n'),
+                (Escape::Backslash, Some('r')) => unescaped.push('\r'),
+                (Escape::Backslash, Some('t')) => unescaped.push('\t'),
+                (Escape::Backslash, Some('0')) => unescaped.push('\0'),
+                (_, Some(c)) => unescaped.push(c),
+                // A lone trailing escape is kept as is.
+                (_, None) => unescaped.push(c),
+            }
+        }
+        Cow::Owned(unescaped)
+    }
+}
+
+impl Pattern<str> for Quoted {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        let start = haystack.find(self.quote)?;
+        let open_end = start + self.quote.len_utf8();
+        let close = open_end + self.closing(&haystack[open_end..])?;
+        Some(start..close + self.quote.len_utf8())
+    }
+
+    // Quotes inside literals do not start new ones, so this scans from the start.
+    fn rfind_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        let mut last = None;
+        let mut from = 0;
+        while let Some(found) = self.find_in(&haystack[from..]) {
+            last = Some(from + found.start..from + found.end);
+            from += found.end;
+        }
+        last
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        if !haystack.starts_with(self.quote) {
+            return None;
+        }
+        self.find_in(haystack).map(|literal| literal.end)
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.rfind_in(haystack)
+            .filter(|literal| literal.end == haystack.len())
+            .map(|literal| literal.len())
+    }
+
+    fn describe(&self) -> String {
+        format!("a '{}'-quoted literal", self.quote.escape_debug())
+    }
+}
+
+impl<'a> ParseCursor<'a> {
+    // Moves the front end over the quoted literal right after it and returns
+    // its unescaped contents. The cursor then covers the literal, quotes
+    // included.
+    pub fn take_quoted(&mut self, quoted: Quoted) -> Result<Cow<'a, str>, CursorError> {
+        self.step_value(|c| {
+            c.back_to_front();
+            let literal = c.front_forward(quoted, PatternLoc::BeginningOnce)?.cursor();
+            let quote_len = quoted.quote.len_utf8();
+            Ok(quoted.unescape(&literal[quote_len..literal.len() - quote_len]))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InwardStrategy;
+
+    #[test]
+    fn test_take_quoted_backslash() {
+        let mut cursor = ParseCursor::new_empty_start(r#""plain" "say \"hi\"\n" 'it\'s'"#);
+        let plain = cursor.take_quoted(Quoted::double()).unwrap();
+        assert!(matches!(plain, Cow::Borrowed("plain")));
+        cursor
+            .front_forward(' ', PatternLoc::BeginningOnce)
+            .unwrap();
+        let escaped = cursor.take_quoted(Quoted::double()).unwrap();
+        assert_eq!(escaped, "say \"hi\"\n");
+        assert_eq!(cursor.cursor(), r#""say \"hi\"\n""#);
+        cursor
+            .front_forward(' ', PatternLoc::BeginningOnce)
+            .unwrap();
+        assert_eq!(cursor.take_quoted(Quoted::single()).unwrap(), "it's");
+    }
+
+    #[test]
+    fn test_doubled_quotes() {
+        let csv = Quoted::new('"', Escape::Doubled);
+        let mut cursor = ParseCursor::new_empty_start(r#"a,"b ""c"", d",e"#);
+        cursor
+            .front_forward(',', PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.take_quoted(csv).unwrap(), r#"b "c", d"#);
+        assert_eq!(cursor.front_rem(), ",e");
+    }
+
+    #[test]
+    fn test_quoted_in_moves() {
+        let data = r#"key = "a \" b" # "comment""#;
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward(Quoted::double(), PatternLoc::FirstIncluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), r#"key = "a \" b""#);
+        cursor.front_to_back();
+        let err = cursor
+            .front_forward(Quoted::single(), PatternLoc::FirstExcluded)
+            .unwrap_err();
+        assert_eq!(err.pattern(), r"a '\''-quoted literal");
+
+        let mut cursor = ParseCursor::new_full(data);
+        cursor
+            .front_backward(
+                Quoted::double(),
+                PatternLoc::FirstIncluded,
+                InwardStrategy::CursorOnly,
+            )
+            .unwrap();
+        assert_eq!(cursor.front_rem(), r#""comment""#);
+
+        let mut cursor = ParseCursor::new_empty_start(r#""unterminated \""#);
+        assert!(cursor.take_quoted(Quoted::double()).is_err());
+        assert_eq!(Quoted::new('\'', Escape::Raw).unescape(r"a\b"), r"a\b");
+    }
+}
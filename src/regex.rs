@@ -0,0 +1,781 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::{ParseCursor, Pattern, Span};
+
+// A small backtracking-free regular expression engine: literals, `.`, classes
+// such as `[a-z_]`, `[^,]`, `\d`, `\w` and `\s`, the greedy and lazy
+// repetitions `*`, `+`, `?` and `{n,m}`, alternation, `^`, `$`, `\b`, `\B`,
+// and groups, capturing unless written `(?:...)`. `^` and `$` are the start
+// and end of the searched text, which for moves is the view being searched.
+//
+// Searches run a Pike VM, so they take time linear in the text for a given
+// expression. Backward searches run the expression compiled in reverse from
+// the end of the text, which finds the match ending last.
+#[derive(Clone, Debug)]
+pub struct Regex {
+    source: String,
+    forward: Vec<Inst>,
+    backward: Vec<Inst>,
+    groups: usize,
+    // Captures of the last search, as offsets into the text searched, which
+    // started at address `searched`. Empty if it failed.
+    searched: usize,
+    searched_len: usize,
+    last: Vec<Option<usize>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegexError {
+    // In chars into the expression.
+    position: usize,
+    message: String,
+}
+
+impl RegexError {
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for RegexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid regular expression: {} at char {}",
+            self.message, self.position
+        )
+    }
+}
+
+impl std::error::Error for RegexError {}
+
+#[derive(Clone, Debug)]
+struct Class {
+    ranges: Vec<(char, char)>,
+    negated: bool,
+}
+
+impl Class {
+    fn of(ranges: &[(char, char)], negated: bool) -> Self {
+        Self {
+            ranges: ranges.to_vec(),
+            negated,
+        }
+    }
+
+    fn matches(&self, c: char) -> bool {
+        self.ranges.iter().any(|&(lo, hi)| lo <= c && c <= hi) != self.negated
+    }
+}
+
+const DIGIT: &[(char, char)] = &[('0', '9')];
+const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')];
+const SPACE: &[(char, char)] = &[('\t', '\r'), (' ', ' ')];
+
+// What `\w` matches and `\b` looks for on either side, ASCII only.
+fn is_word_char(c: char) -> bool {
+    WORD.iter()
+        .any(|&(first, last)| (first..=last).contains(&c))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Look {
+    Start,
+    End,
+    WordBoundary,
+    NotWordBoundary,
+}
+
+impl Look {
+    fn holds(self, text: &str, pos: usize) -> bool {
+        let is_word = |c: Option<char>| c.is_some_and(is_word_char);
+        let boundary =
+            || is_word(text[..pos].chars().next_back()) != is_word(text[pos..].chars().next());
+        match self {
+            Look::Start => pos == 0,
+            Look::End => pos == text.len(),
+            Look::WordBoundary => boundary(),
+            Look::NotWordBoundary => !boundary(),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Node {
+    Empty,
+    Char(char),
+    Class(Class),
+    Look(Look),
+    Concat(Vec<Node>),
+    Alt(Vec<Node>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+        greedy: bool,
+    },
+    Group(Option<usize>, Box<Node>),
+}
+
+// Bounded repetitions are compiled by copying their operand, so both the
+// count and the whole program are limited.
+const MAX_REPEAT: u32 = 1000;
+const MAX_INSTS: usize = 100_000;
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    groups: usize,
+}
+
+impl Parser {
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, RegexError> {
+        Err(RegexError {
+            position: self.pos,
+            message: message.into(),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let eaten = self.peek() == Some(c);
+        if eaten {
+            self.pos += 1;
+        }
+        eaten
+    }
+
+    fn next(&mut self) -> Result<char, RegexError> {
+        let c = self.peek();
+        self.pos += 1;
+        c.map_or_else(|| self.error("unexpected end"), Ok)
+    }
+
+    fn alternation(&mut self) -> Result<Node, RegexError> {
+        let mut alternatives = vec![self.concatenation()?];
+        while self.eat('|') {
+            alternatives.push(self.concatenation()?);
+        }
+        Ok(if alternatives.len() == 1 {
+            alternatives.pop().unwrap()
+        } else {
+            Node::Alt(alternatives)
+        })
+    }
+
+    fn concatenation(&mut self) -> Result<Node, RegexError> {
+        let mut nodes = Vec::new();
+        while let Some(c) = self.peek() {
+            if c == '|' || c == ')' {
+                break;
+            }
+            let atom = self.atom()?;
+            nodes.push(self.repetition(atom)?);
+        }
+        Ok(match nodes.len() {
+            0 => Node::Empty,
+            1 => nodes.pop().unwrap(),
+            _ => Node::Concat(nodes),
+        })
+    }
+
+    fn atom(&mut self) -> Result<Node, RegexError> {
+        Ok(match self.next()? {
+            '(' => {
+                let index = if self.eat('?') {
+                    if !self.eat(':') {
+                        return self.error("unknown group flag");
+                    }
+                    None
+                } else {
+                    self.groups += 1;
+                    Some(self.groups)
+                };
+                let inner = self.alternation()?;
+                if !self.eat(')') {
+                    return self.error("unclosed group");
+                }
+                Node::Group(index, Box::new(inner))
+            }
+            '[' => Node::Class(self.class()?),
+            '.' => Node::Class(Class::of(&[('\n', '\n')], true)),
+            '^' => Node::Look(Look::Start),
+            '$' => Node::Look(Look::End),
+            '\\' => match self.next()? {
+                'b' => Node::Look(Look::WordBoundary),
+                'B' => Node::Look(Look::NotWordBoundary),
+                'd' => Node::Class(Class::of(DIGIT, false)),
+                'D' => Node::Class(Class::of(DIGIT, true)),
+                'w' => Node::Class(Class::of(WORD, false)),
+                'W' => Node::Class(Class::of(WORD, true)),
+                's' => Node::Class(Class::of(SPACE, false)),
+                'S' => Node::Class(Class::of(SPACE, true)),
+                _ => {
+                    self.pos -= 1;
+                    Node::Char(self.escaped()?)
+                }
+            },
+            '*' | '+' | '?' | '{' => {
+                self.pos -= 1;
+                return self.error("nothing to repeat");
+            }
+            c => Node::Char(c),
+        })
+    }
+
+    // The char after a `\` that stands for a single char.
+    fn escaped(&mut self) -> Result<char, RegexError> {
+        Ok(match self.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            c if c.is_alphanumeric() => {
+                self.pos -= 1;
+                return self.error(format!("unknown escape `\\{c}`"));
+            }
+            c => c,
+        })
+    }
+
+    fn class(&mut self) -> Result<Class, RegexError> {
+        let negated = self.eat('^');
+        let mut ranges = Vec::new();
+        let mut first = true;
+        loop {
+            let c = self.next()?;
+            if c == ']' && !first {
+                break;
+            }
+            first = false;
+            let lo = if c == '\\' {
+                let set = match self.peek() {
+                    Some('d') => DIGIT,
+                    Some('w') => WORD,
+                    Some('s') => SPACE,
+                    _ => &[],
+                };
+                if !set.is_empty() {
+                    self.pos += 1;
+                    ranges.extend_from_slice(set);
+                    continue;
+                }
+                self.escaped()?
+            } else {
+                c
+            };
+            let is_range = self.peek() == Some('-') && self.chars.get(self.pos + 1) != Some(&']');
+            if !is_range {
+                ranges.push((lo, lo));
+                continue;
+            }
+            self.pos += 1;
+            let hi = match self.next()? {
+                '\\' => self.escaped()?,
+                hi => hi,
+            };
+            if hi < lo {
+                return self.error("class range out of order");
+            }
+            ranges.push((lo, hi));
+        }
+        Ok(Class { ranges, negated })
+    }
+
+    fn number(&mut self) -> Option<u32> {
+        let start = self.pos;
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let digits: String = self.chars[start..self.pos].iter().collect();
+        digits.parse().ok()
+    }
+
+    fn repetition(&mut self, atom: Node) -> Result<Node, RegexError> {
+        let (min, max) = match self.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.pos += 1;
+                let Some(min) = self.number() else {
+                    return self.error("expected a repetition count");
+                };
+                let max = if self.eat(',') {
+                    self.number()
+                } else {
+                    Some(min)
+                };
+                if !self.eat('}') {
+                    return self.error("unclosed repetition");
+                }
+                if max.is_some_and(|max| max < min) || max.unwrap_or(min) > MAX_REPEAT {
+                    return self.error("invalid repetition count");
+                }
+                self.pos -= 1;
+                (min, max)
+            }
+            _ => return Ok(atom),
+        };
+        self.pos += 1;
+        let greedy = !self.eat('?');
+        if matches!(self.peek(), Some('*' | '+' | '?' | '{')) {
+            return self.error("nothing to repeat");
+        }
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+            greedy,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Inst {
+    Char(char),
+    Class(Class),
+    Look(Look),
+    Save(usize),
+    // Tries the first target before the second.
+    Split(usize, usize),
+    Jmp(usize),
+    Match,
+}
+
+impl Node {
+    // How many instructions `Compiler::emit` makes of the node, saturating.
+    fn size(&self) -> usize {
+        match self {
+            Node::Empty => 0,
+            Node::Char(_) | Node::Class(_) | Node::Look(_) => 1,
+            Node::Concat(nodes) => nodes
+                .iter()
+                .fold(0, |size, n| size.saturating_add(n.size())),
+            Node::Alt(alternatives) => alternatives
+                .iter()
+                .fold(2 * (alternatives.len() - 1), |size, n| {
+                    size.saturating_add(n.size())
+                }),
+            Node::Repeat { node, min, max, .. } => {
+                let size = node.size();
+                let required = size.saturating_mul(*min as usize);
+                match max {
+                    Some(max) => required.saturating_add(
+                        size.saturating_add(1).saturating_mul((max - min) as usize),
+                    ),
+                    None => required.saturating_add(size.saturating_add(2)),
+                }
+            }
+            Node::Group(index, inner) => inner.size() + if index.is_some() { 2 } else { 0 },
+        }
+    }
+}
+
+struct Compiler {
+    insts: Vec<Inst>,
+    reverse: bool,
+}
+
+impl Compiler {
+    fn compile(root: &Node, reverse: bool) -> Vec<Inst> {
+        let mut compiler = Compiler {
+            insts: Vec::new(),
+            reverse,
+        };
+        compiler.emit(root);
+        compiler.insts.push(Inst::Match);
+        compiler.insts
+    }
+
+    fn push(&mut self, inst: Inst) -> usize {
+        self.insts.push(inst);
+        self.insts.len() - 1
+    }
+
+    fn split(&mut self, at: usize, body: usize, skip: usize, greedy: bool) {
+        self.insts[at] = if greedy {
+            Inst::Split(body, skip)
+        } else {
+            Inst::Split(skip, body)
+        };
+    }
+
+    fn emit(&mut self, node: &Node) {
+        match node {
+            Node::Empty => (),
+            Node::Char(c) => {
+                self.push(Inst::Char(*c));
+            }
+            Node::Class(class) => {
+                self.push(Inst::Class(class.clone()));
+            }
+            Node::Look(look) => {
+                self.push(Inst::Look(*look));
+            }
+            Node::Concat(nodes) if self.reverse => nodes.iter().rev().for_each(|n| self.emit(n)),
+            Node::Concat(nodes) => nodes.iter().for_each(|n| self.emit(n)),
+            Node::Alt(alternatives) => {
+                let mut jumps = Vec::new();
+                for (i, alternative) in alternatives.iter().enumerate() {
+                    if i + 1 == alternatives.len() {
+                        self.emit(alternative);
+                        break;
+                    }
+                    let split = self.push(Inst::Jmp(0));
+                    self.emit(alternative);
+                    jumps.push(self.push(Inst::Jmp(0)));
+                    let next = self.insts.len();
+                    self.split(split, split + 1, next, true);
+                }
+                let end = self.insts.len();
+                for jump in jumps {
+                    self.insts[jump] = Inst::Jmp(end);
+                }
+            }
+            Node::Repeat {
+                node,
+                min,
+                max,
+                greedy,
+            } => {
+                for _ in 0..*min {
+                    self.emit(node);
+                }
+                match max {
+                    Some(max) => {
+                        for _ in *min..*max {
+                            let split = self.push(Inst::Jmp(0));
+                            self.emit(node);
+                            let end = self.insts.len();
+                            self.split(split, split + 1, end, *greedy);
+                        }
+                    }
+                    None => {
+                        let split = self.push(Inst::Jmp(0));
+                        self.emit(node);
+                        self.push(Inst::Jmp(split));
+                        let end = self.insts.len();
+                        self.split(split, split + 1, end, *greedy);
+                    }
+                }
+            }
+            Node::Group(index, inner) => {
+                let Some(index) = index else {
+                    return self.emit(inner);
+                };
+                let (first, last) = if self.reverse {
+                    (2 * index + 1, 2 * index)
+                } else {
+                    (2 * index, 2 * index + 1)
+                };
+                self.push(Inst::Save(first));
+                self.emit(inner);
+                self.push(Inst::Save(last));
+            }
+        }
+    }
+}
+
+struct Thread {
+    pc: usize,
+    slots: Vec<Option<usize>>,
+}
+
+// Runs `prog` over `text` from its start, or from its end when `backward`,
+// returning the slots of the first match by priority among those starting
+// earliest in the direction of the search.
+fn execute(
+    prog: &[Inst],
+    slot_count: usize,
+    text: &str,
+    anchored: bool,
+    backward: bool,
+) -> Option<Vec<Option<usize>>> {
+    let start = if backward { text.len() } else { 0 };
+    let mut pos = start;
+    let mut current = Vec::new();
+    let mut seen = vec![false; prog.len()];
+    let mut matched = None;
+    loop {
+        if matched.is_none() && (!anchored || pos == start) {
+            let slots = vec![None; slot_count];
+            add_thread(prog, text, pos, &mut current, &mut seen, 0, slots);
+        }
+        // Unanchored searches go on seeding until something matches.
+        if current.is_empty() && (anchored || matched.is_some()) {
+            break;
+        }
+        let c = if backward {
+            text[..pos].chars().next_back()
+        } else {
+            text[pos..].chars().next()
+        };
+        let next_pos = c.map(|c| {
+            if backward {
+                pos - c.len_utf8()
+            } else {
+                pos + c.len_utf8()
+            }
+        });
+        let mut next = Vec::new();
+        let mut next_seen = vec![false; prog.len()];
+        for Thread { pc, slots } in current.drain(..) {
+            let step = match &prog[pc] {
+                Inst::Char(expected) => c == Some(*expected),
+                Inst::Class(class) => c.is_some_and(|c| class.matches(c)),
+                Inst::Match => {
+                    // Threads after this one have a lower priority.
+                    matched = Some(slots);
+                    break;
+                }
+                _ => unreachable!("only consuming instructions are queued"),
+            };
+            if let (true, Some(next_pos)) = (step, next_pos) {
+                add_thread(
+                    prog,
+                    text,
+                    next_pos,
+                    &mut next,
+                    &mut next_seen,
+                    pc + 1,
+                    slots,
+                );
+            }
+        }
+        current = next;
+        seen = next_seen;
+        match next_pos {
+            Some(next_pos) => pos = next_pos,
+            None => break,
+        }
+    }
+    matched
+}
+
+fn add_thread(
+    prog: &[Inst],
+    text: &str,
+    pos: usize,
+    list: &mut Vec<Thread>,
+    seen: &mut [bool],
+    pc: usize,
+    slots: Vec<Option<usize>>,
+) {
+    // Followed depth first, the top of the stack having the highest priority.
+    let mut stack = vec![(pc, slots)];
+    while let Some((pc, mut slots)) = stack.pop() {
+        if seen[pc] {
+            continue;
+        }
+        seen[pc] = true;
+        match &prog[pc] {
+            Inst::Jmp(target) => stack.push((*target, slots)),
+            Inst::Split(first, second) => {
+                stack.push((*second, slots.clone()));
+                stack.push((*first, slots));
+            }
+            Inst::Save(slot) => {
+                slots[*slot] = Some(pos);
+                stack.push((pc + 1, slots));
+            }
+            Inst::Look(look) => {
+                if look.holds(text, pos) {
+                    stack.push((pc + 1, slots));
+                }
+            }
+            _ => list.push(Thread { pc, slots }),
+        }
+    }
+}
+
+impl Regex {
+    pub fn new(source: &str) -> Result<Self, RegexError> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            pos: 0,
+            groups: 0,
+        };
+        let root = parser.alternation()?;
+        if parser.pos < parser.chars.len() {
+            return parser.error("unmatched `)`");
+        }
+        let root = Node::Group(Some(0), Box::new(root));
+        if root.size() >= MAX_INSTS {
+            return Err(RegexError {
+                position: 0,
+                message: format!("expression compiles to more than {MAX_INSTS} instructions"),
+            });
+        }
+        Ok(Self {
+            source: source.to_owned(),
+            forward: Compiler::compile(&root, false),
+            backward: Compiler::compile(&root, true),
+            groups: parser.groups,
+            searched: 0,
+            searched_len: 0,
+            last: Vec::new(),
+        })
+    }
+
+    // Capturing groups, not counting the whole match.
+    pub fn group_count(&self) -> usize {
+        self.groups
+    }
+
+    fn search(&mut self, text: &str, anchored: bool, backward: bool) -> Option<Range<usize>> {
+        self.last.clear();
+        let prog = if backward {
+            &self.backward
+        } else {
+            &self.forward
+        };
+        let slots = execute(prog, 2 * (self.groups + 1), text, anchored, backward)?;
+        let found = slots[0]?..slots[1]?;
+        self.searched = text.as_ptr() as usize;
+        self.searched_len = text.len();
+        self.last = slots;
+        Some(found)
+    }
+}
+
+impl Pattern<str> for Regex {
+    fn find_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        self.search(haystack, false, false)
+    }
+
+    fn rfind_in(&mut self, haystack: &str) -> Option<Range<usize>> {
+        self.search(haystack, false, true)
+    }
+
+    fn prefix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.search(haystack, true, false).map(|found| found.end)
+    }
+
+    fn suffix_len(&mut self, haystack: &str) -> Option<usize> {
+        self.search(haystack, true, true).map(|found| found.len())
+    }
+
+    fn describe(&self) -> String {
+        format!("/{}/", self.source)
+    }
+}
+
+impl ParseCursor<'_> {
+    // Where the groups of the last search of `regex` are in `data`, the whole
+    // match first. Groups that took no part are `None`, and so are all of
+    // them if that search failed or was not over `data`.
+    pub fn captures(&self, regex: &Regex) -> Vec<Option<Span>> {
+        let base = (regex.searched)
+            .checked_sub(self.data.as_ptr() as usize)
+            .filter(|base| base + regex.searched_len <= self.data.len());
+        let mut captures = vec![None; regex.groups + 1];
+        if let Some(base) = base {
+            for (capture, slots) in captures.iter_mut().zip(regex.last.chunks(2)) {
+                if let [Some(start), Some(end)] = *slots {
+                    *capture = Some(Span::new(base + start, base + end));
+                }
+            }
+        }
+        captures
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InwardStrategy, PatternLoc};
+
+    fn find(regex: &str, text: &str) -> Option<Range<usize>> {
+        Regex::new(regex).unwrap().find_in(text)
+    }
+
+    #[test]
+    fn test_regex_matching() {
+        assert_eq!(find(r"[A-Z]{3}-\d{4}", "id: ABC-1234!"), Some(4..12));
+        assert_eq!(find(r"[A-Z]{3}-\d{4}", "id: AB-1234"), None);
+        assert_eq!(find("a+?", "caaa"), Some(1..2));
+        assert_eq!(find("a+", "caaa"), Some(1..4));
+        assert_eq!(find("cat|category", "category"), Some(0..3));
+        assert_eq!(find(r"\bend$", "legend end"), Some(7..10));
+        assert_eq!(find(r"^b", "ab"), None);
+        assert_eq!(find("x*", "abc"), Some(0..0));
+        assert_eq!(find("[^,]+", ",éa,b"), Some(1..4));
+        assert_eq!(find(r"[\d.-]+", "v -1.5e"), Some(2..6));
+        assert_eq!(find("(?:ab){2,}", "ab abababx"), Some(3..9));
+        // `\b` and `\w` agree that only ASCII letters, digits and `_` make words.
+        assert_eq!(find(r"\w+", "éte"), Some(2..4));
+        assert_eq!(find(r"\bte", "éte"), Some(2..4));
+        assert_eq!(find(r"\Bte", "éte"), None);
+
+        let mut regex = Regex::new(r"\d+").unwrap();
+        assert_eq!(regex.rfind_in("12 345 6x"), Some(7..8));
+        assert_eq!(regex.suffix_len("ab12"), Some(2));
+        assert_eq!(regex.prefix_len("ab12"), None);
+
+        let err = Regex::new("a(b|c").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "invalid regular expression: unclosed group at char 5"
+        );
+        assert!(Regex::new("*a").is_err());
+        assert!(Regex::new(r"\q").is_err());
+        assert!(Regex::new("a{3,2}").is_err());
+        let err = Regex::new("(?:(?:a?){1000}){1000}").unwrap_err();
+        assert_eq!(
+            err.message(),
+            "expression compiles to more than 100000 instructions"
+        );
+        assert!(Regex::new("(?:(?:(?:a){1000}){1000}){1000}").is_err());
+        assert!(Regex::new("(?:a?){1000}").is_ok());
+    }
+
+    #[test]
+    fn test_regex_moves_and_captures() {
+        let data = "ERROR: oops (/src/main.rs at line 42, column 7)";
+        let mut location = Regex::new(r"at line (\d+), column (\d+)").unwrap();
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward(location.by_ref(), PatternLoc::FirstIncluded)
+            .unwrap();
+        let captures = cursor.captures(&location);
+        assert_eq!(captures[1].unwrap().resolve(data), "42");
+        assert_eq!(captures[2].unwrap().resolve(data), "7");
+        assert_eq!(captures[0].unwrap().end(), cursor.span().end());
+
+        let mut path = Regex::new(r"/(\w+/)*(\w+)\.rs").unwrap();
+        let mut cursor = ParseCursor::new_empty_end(data);
+        cursor
+            .front_backward(
+                path.by_ref(),
+                PatternLoc::FirstIncluded,
+                InwardStrategy::WholeData,
+            )
+            .unwrap();
+        assert!(cursor.front_rem().starts_with("/src/main.rs at"));
+        let captures = cursor.captures(&path);
+        assert_eq!(captures[1].unwrap().resolve(data), "src/");
+        assert_eq!(captures[2].unwrap().resolve(data), "main");
+
+        let mut cursor = ParseCursor::new_empty_start(data);
+        let err = cursor
+            .front_forward(Regex::new(r"line \d+;").unwrap(), PatternLoc::FirstExcluded)
+            .unwrap_err();
+        assert_eq!(err.pattern(), r"/line \d+;/");
+        assert!(path.find_in("no path").is_none());
+        assert_eq!(cursor.captures(&path), [None, None, None]);
+        let other = String::from(data);
+        assert_eq!(
+            ParseCursor::new_full(other.as_str()).captures(&path)[2],
+            None
+        );
+    }
+}
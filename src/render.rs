@@ -0,0 +1,224 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::{CursorError, ErrorKind, ParseCursor};
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic<'a> {
+    data: &'a str,
+    // None for errors that do not say where they happened.
+    span: Option<Range<usize>>,
+    message: String,
+}
+
+pub struct Colored<'d, 'a>(&'d Diagnostic<'a>);
+
+struct Style {
+    error: &'static str,
+    gutter: &'static str,
+    message: &'static str,
+    reset: &'static str,
+}
+
+const PLAIN: Style = Style {
+    error: "",
+    gutter: "",
+    message: "",
+    reset: "",
+};
+
+const ANSI: Style = Style {
+    error: RED,
+    gutter: BLUE,
+    message: BOLD,
+    reset: RESET,
+};
+
+impl<'a> Diagnostic<'a> {
+    pub fn new(data: &'a str, span: Range<usize>, message: impl fmt::Display) -> Self {
+        assert!(span.start <= span.end);
+        assert!(data.get(span.clone()).is_some());
+        Self {
+            data,
+            span: Some(span),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn from_error(data: &'a str, err: &CursorError) -> Self {
+        match err.kind() {
+            ErrorKind::Unlocated => Self {
+                data,
+                span: None,
+                message: err.to_string(),
+            },
+            _ => Self::new(data, err.offset()..err.offset(), err),
+        }
+    }
+
+    pub fn colored(&self) -> Colored<'_, 'a> {
+        Colored(self)
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, style: &Style) -> fmt::Result {
+        writeln!(
+            f,
+            "{}error{}{}: {}{}",
+            style.error, style.reset, style.message, self.message, style.reset
+        )?;
+        let Some(Range { start, end }) = self.span else {
+            return Ok(());
+        };
+        let mut line_start = self.data[..start].rfind('\n').map_or(0, |i| i + 1);
+        let mut line_no = self.data[..start].matches('\n').count() + 1;
+        let column = self.data[line_start..start].chars().count() + 1;
+        let last_line_no = line_no + self.data[start..end].matches('\n').count();
+        let pad = " ".repeat(last_line_no.to_string().len());
+        writeln!(
+            f,
+            "{pad}{}-->{} {line_no}:{column}",
+            style.gutter, style.reset
+        )?;
+        writeln!(f, "{pad} {}|{}", style.gutter, style.reset)?;
+        loop {
+            let line_end = self.data[line_start..]
+                .find('\n')
+                .map_or(self.data.len(), |i| line_start + i);
+            let line = &self.data[line_start..line_end];
+            let text = line.strip_suffix('\r').unwrap_or(line);
+            let text_end = line_start + text.len();
+            let from = start.max(line_start).min(text_end);
+            let to = end.min(text_end).max(from);
+            // Keep tabs so the carets line up with the rendered text.
+            let indent: String = self.data[line_start..from]
+                .chars()
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            let width = self.data[from..to].chars().count().max(1);
+            writeln!(
+                f,
+                "{}{line_no:>w$} |{} {text}",
+                style.gutter,
+                style.reset,
+                w = pad.len()
+            )?;
+            writeln!(
+                f,
+                "{pad} {}|{} {indent}{}{}{}",
+                style.gutter,
+                style.reset,
+                style.error,
+                "^".repeat(width),
+                style.reset
+            )?;
+            if end <= line_end + 1 || line_end == self.data.len() {
+                return Ok(());
+            }
+            line_start = line_end + 1;
+            line_no += 1;
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.render(f, &PLAIN)
+    }
+}
+
+impl fmt::Display for Colored<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.render(f, &ANSI)
+    }
+}
+
+impl<'a> ParseCursor<'a> {
+    pub fn diagnostic(&self, message: impl fmt::Display) -> Diagnostic<'a> {
+        Diagnostic::new(self.data(), self.cursor_range(), message)
+    }
+
+    pub fn report(&self, err: &CursorError) -> Diagnostic<'a> {
+        Diagnostic::from_error(self.data(), err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{InwardStrategy, PatternLoc};
+
+    #[test]
+    fn test_single_line_span() {
+        let mut cursor = ParseCursor::new_empty_start("first\nERROR: bad thing\nlast");
+        cursor
+            .back_forward(
+                "ERROR: ",
+                PatternLoc::FirstIncluded,
+                InwardStrategy::WholeData,
+            )
+            .unwrap()
+            .front_forward('\n', PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(
+            cursor.diagnostic("unknown message").to_string(),
+            "error: unknown message\n\
+             \x20--> 2:8\n\
+             \x20 |\n\
+             2 | ERROR: bad thing\n\
+             \x20 |        ^^^^^^^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_error_caret_at_failing_end() {
+        let mut cursor = ParseCursor::new_empty_start("(/etc/baz at line 0)");
+        let err = cursor
+            .front_forward(", column ", PatternLoc::FirstExcluded)
+            .unwrap_err();
+        let rendered = cursor.report(&err).to_string();
+        assert!(rendered.starts_with("error: expected ', column '"));
+        assert!(rendered.ends_with("1 | (/etc/baz at line 0)\n  | ^\n"));
+    }
+
+    #[test]
+    fn test_unlocated_error_has_no_caret() {
+        let err = CursorError::from(crate::Failed);
+        assert_eq!(err.to_string(), "failed");
+        let cursor = ParseCursor::new_full("abc");
+        assert_eq!(cursor.report(&err).to_string(), "error: failed\n");
+    }
+
+    #[test]
+    fn test_multi_line_span() {
+        let data = "one\r\ntwo\nthree";
+        let diagnostic = Diagnostic::new(data, 1..8, "spans lines");
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: spans lines\n\
+             \x20--> 1:2\n\
+             \x20 |\n\
+             1 | one\n\
+             \x20 |  ^^\n\
+             2 | two\n\
+             \x20 | ^^^\n"
+        );
+    }
+
+    #[test]
+    fn test_colored_wraps_plain_output() {
+        let diagnostic = Diagnostic::new("abc", 1..2, "oops");
+        let colored = diagnostic.colored().to_string();
+        assert!(colored.contains(RED));
+        let stripped = colored
+            .replace(RED, "")
+            .replace(BLUE, "")
+            .replace(BOLD, "")
+            .replace(RESET, "");
+        assert_eq!(stripped, diagnostic.to_string());
+    }
+}
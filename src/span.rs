@@ -0,0 +1,140 @@
+use std::ops::Range;
+
+use crate::{Haystack, ParseCursor};
+
+// A byte range into the `data` of a cursor, cheap to keep around and to
+// resolve again later. Spans are ordered by start, then by end.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Span {
+    start: usize,
+    end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        assert!(start <= end);
+        Self { start, end }
+    }
+
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    // The smallest span covering both, including what lies between them.
+    pub fn union(self, other: Self) -> Self {
+        Self::new(self.start.min(other.start), self.end.max(other.end))
+    }
+
+    // `None` when they do not overlap. Spans only touching at an end share an
+    // empty span there.
+    pub fn intersection(self, other: Self) -> Option<Self> {
+        let start = self.start.max(other.start);
+        let end = self.end.min(other.end);
+        (start <= end).then(|| Self::new(start, end))
+    }
+
+    pub fn contains(&self, other: Self) -> bool {
+        self.start <= other.start && other.end <= self.end
+    }
+
+    pub fn contains_offset(&self, offset: usize) -> bool {
+        self.start <= offset && offset < self.end
+    }
+
+    pub fn resolve<'a, H: ?Sized + Haystack>(&self, data: &'a H) -> &'a H {
+        data.slice(self.start..self.end)
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
+
+impl<'a, H: ?Sized + Haystack> ParseCursor<'a, H> {
+    pub fn span(&self) -> Span {
+        Span::new(self.cursor_range.start, self.cursor_range.end)
+    }
+
+    // The span of a slice of `data`, such as one returned by `cursor` or
+    // `split`, found from where it points to. `None` if it is not in `data`.
+    pub fn span_of(&self, slice: &H) -> Option<Span> {
+        let data = self.data.as_bytes().as_ptr() as usize;
+        let start = (slice.as_bytes().as_ptr() as usize).checked_sub(data)?;
+        let end = start + slice.len();
+        (end <= self.data.len()).then(|| Span::new(start, end))
+    }
+
+    pub fn at_span(data: &'a H, span: Span) -> Self {
+        assert!(data.get(span.into()).is_some());
+        Self::with_range(data, span.into())
+    }
+
+    // Moves both ends to those of `span`, which has to be within the bounds.
+    pub fn select_span(&mut self, span: Span) -> &mut Self {
+        assert!(self.bounds.start <= span.start && span.end <= self.bounds.end);
+        assert!(self.data.get(span.into()).is_some());
+        self.cursor_range = span.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ByteCursor, PatternLoc};
+
+    #[test]
+    fn test_span_algebra() {
+        let a = Span::new(2, 6);
+        let b = Span::new(4, 9);
+        assert_eq!(a.union(b), Span::new(2, 9));
+        assert_eq!(a.intersection(b), Some(Span::new(4, 6)));
+        assert_eq!(a.intersection(Span::new(6, 8)), Some(Span::new(6, 6)));
+        assert_eq!(a.intersection(Span::new(7, 8)), None);
+        assert!(a.union(b).contains(b));
+        assert!(!a.contains(b));
+        assert!(a.contains_offset(5) && !a.contains_offset(6));
+        let mut spans = [b, Span::new(2, 3), a];
+        spans.sort();
+        assert_eq!(spans, [Span::new(2, 3), a, b]);
+    }
+
+    #[test]
+    fn test_spans_from_cursors_and_slices() {
+        let data = "name = value";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        cursor
+            .front_forward(' ', PatternLoc::FirstExcluded)
+            .unwrap();
+        let name = cursor.span();
+        let (_, _, rest) = cursor.split();
+        let value = cursor.span_of(rest.trim_start_matches([' ', '='])).unwrap();
+        assert_eq!(value, Span::new(7, 12));
+        assert_eq!(value.resolve(data), "value");
+        assert_eq!(name.resolve(data), "name");
+        assert_eq!(cursor.span_of(&String::from("value")), None);
+
+        let cursor = ParseCursor::at_span(data, value);
+        assert_eq!(cursor.cursor(), "value");
+        assert_eq!(cursor.back_rem(), "name = ");
+
+        let mut bytes = ByteCursor::new_full(b"\xffab");
+        let span = bytes.span_of(&bytes.data()[1..]).unwrap();
+        bytes.select_span(span);
+        assert_eq!(bytes.cursor(), b"ab");
+    }
+}
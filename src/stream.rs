@@ -0,0 +1,643 @@
+use std::fmt;
+use std::io::{self, BufRead};
+use std::ops::Range;
+
+use crate::{
+    CursorError, Direction, End, FallBack, Haystack, InwardStrategy, Pattern, PatternLoc,
+    find_directional_offset, nth_from_start, trimmed_start_len,
+};
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Cursor(CursorError),
+}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> Self {
+        StreamError::Io(err)
+    }
+}
+
+impl From<CursorError> for StreamError {
+    fn from(err: CursorError) -> Self {
+        StreamError::Cursor(err)
+    }
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "reading input failed: {err}"),
+            StreamError::Cursor(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StreamError::Io(err) => Some(err),
+            StreamError::Cursor(err) => Some(err),
+        }
+    }
+}
+
+// Whether a `BeginningOnce` search of `view` is final: a match followed by
+// another char, or no match while `view` is as long as any match can be. For
+// patterns without such a bound, no match while the pattern matches further on.
+fn prefix_settled(view: &str, pattern: &mut impl Pattern) -> bool {
+    match pattern.prefix_len(view) {
+        Some(len) => len < view.len(),
+        None => match pattern.max_len() {
+            Some(max_len) => view.len() >= max_len,
+            None => pattern
+                .find_in(view)
+                .is_some_and(|m| m.start > 0 && m.end < view.len()),
+        },
+    }
+}
+
+// Same cursor model as `ParseCursor`, but `data` is only the buffered window
+// of the stream. All offsets are absolute positions in the stream; `base` is
+// the absolute offset of `buf[0]`. What comes before the back end is dropped
+// on the next refill, unless a running `step` still needs it, so backward
+// moves only see what is left of it.
+pub struct StreamCursor<R> {
+    reader: R,
+    buf: String,
+    // Bytes read but not yet forming a complete char.
+    pending: Vec<u8>,
+    base: usize,
+    cursor_range: Range<usize>,
+    // Lowest offset a running `step` may roll back to.
+    pinned: Option<usize>,
+    eof: bool,
+}
+
+impl<R> fmt::Debug for StreamCursor<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamCursor")
+            .field("cursor_range", &self.cursor_range)
+            .field("buffered", &(self.base..self.base + self.buf.len()))
+            .field("eof", &self.eof)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: BufRead> StreamCursor<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            buf: String::new(),
+            pending: Vec::new(),
+            base: 0,
+            cursor_range: 0..0,
+            pinned: None,
+            eof: false,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    pub fn front_to_back(&mut self) {
+        self.cursor_range.end = self.cursor_range.start;
+    }
+
+    pub fn back_to_front(&mut self) {
+        self.cursor_range.start = self.cursor_range.end;
+    }
+
+    pub fn back_offset(&self) -> usize {
+        self.cursor_range.start
+    }
+
+    pub fn front_offset(&self) -> usize {
+        self.cursor_range.end
+    }
+
+    pub fn is_at_eof(&self) -> bool {
+        self.eof && self.cursor_range.end == self.base + self.buf.len()
+    }
+
+    fn buffered(&self, range: Range<usize>) -> &str {
+        &self.buf[range.start - self.base..range.end - self.base]
+    }
+
+    pub fn cursor(&self) -> &str {
+        self.buffered(self.cursor_range.clone())
+    }
+
+    pub fn buffered_front_rem(&self) -> &str {
+        self.buffered(self.cursor_range.end..self.buffered_end())
+    }
+
+    fn buffered_end(&self) -> usize {
+        self.base + self.buf.len()
+    }
+
+    fn fill_more(&mut self) -> io::Result<bool> {
+        if self.eof {
+            return Ok(false);
+        }
+        let keep_from = self.pinned.map_or(self.cursor_range.start, |pin| {
+            pin.min(self.cursor_range.start)
+        });
+        self.buf.drain(..keep_from - self.base);
+        self.base = keep_from;
+
+        let chunk = self.reader.fill_buf()?;
+        if chunk.is_empty() {
+            self.eof = true;
+            if !self.pending.is_empty() {
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "stream ended inside a UTF-8 sequence",
+                ));
+            }
+            return Ok(false);
+        }
+        self.pending.extend_from_slice(chunk);
+        let consumed = chunk.len();
+        self.reader.consume(consumed);
+
+        let valid = match std::str::from_utf8(&self.pending) {
+            Ok(valid) => valid,
+            Err(err) if err.error_len().is_none() => {
+                std::str::from_utf8(&self.pending[..err.valid_up_to()]).unwrap()
+            }
+            Err(err) => return Err(io::Error::new(io::ErrorKind::InvalidData, err)),
+        };
+        self.buf.push_str(valid);
+        let valid_len = valid.len();
+        self.pending.drain(..valid_len);
+        Ok(true)
+    }
+
+    // Whether more input could still change the result of searching `view`
+    // forward. A match is taken as final once a char follows it, as patterns
+    // may match greedily, and a failure to match at the start as
+    // `prefix_settled` has it.
+    fn is_settled(&self, view: &str, pattern: &mut impl Pattern, loc: PatternLoc) -> bool {
+        if self.eof {
+            return true;
+        }
+        let complete = |found: Option<Range<usize>>| found.is_some_and(|m| m.end < view.len());
+        match loc {
+            PatternLoc::FirstExcluded | PatternLoc::FirstIncluded => {
+                complete(pattern.find_in(view))
+            }
+            PatternLoc::NthExcluded(n) | PatternLoc::NthIncluded(n) => {
+                complete(nth_from_start(view, pattern, n))
+            }
+            PatternLoc::BeginningOnce => prefix_settled(view, pattern),
+            PatternLoc::BeginningMany => {
+                let trimmed = trimmed_start_len(view, pattern);
+                prefix_settled(&view[trimmed..], pattern)
+            }
+            // Depend on the last occurrence, which is only known at the end.
+            PatternLoc::LastExcluded
+            | PatternLoc::EndOfLast
+            | PatternLoc::StartOfSuffixMany
+            | PatternLoc::NthLastExcluded(_)
+            | PatternLoc::NthLastIncluded(_) => false,
+        }
+    }
+
+    // Searches forward from `from`, refilling until the result is settled.
+    fn locate_forward(
+        &mut self,
+        from: usize,
+        pattern: &mut impl Pattern,
+        loc: PatternLoc,
+    ) -> io::Result<Option<usize>> {
+        while !self.is_settled(self.buffered(from..self.buffered_end()), pattern, loc) {
+            self.fill_more()?;
+        }
+        // Searched last so that the state of the pattern is that of the move.
+        let view = self.buffered(from..self.buffered_end());
+        Ok(find_directional_offset(
+            view,
+            pattern.by_ref(),
+            loc,
+            Direction::Forward,
+        ))
+    }
+
+    // Refills until `len` bytes past `from` are buffered or the input ends.
+    fn fill_to(&mut self, from: usize, len: usize) -> io::Result<()> {
+        while self.buffered_end() - from < len && self.fill_more()? {}
+        Ok(())
+    }
+
+    fn failure(
+        &self,
+        end: End,
+        direction: Direction,
+        loc: PatternLoc,
+        pattern: &impl Pattern,
+    ) -> StreamError {
+        let offset = match end {
+            End::Front => self.cursor_range.end,
+            End::Back => self.cursor_range.start,
+        };
+        CursorError::failed_move(offset, end, direction, loc, pattern).into()
+    }
+
+    pub fn front_forward(
+        &mut self,
+        mut pattern: impl Pattern,
+        loc: PatternLoc,
+    ) -> Result<&mut Self, StreamError> {
+        let by = self
+            .locate_forward(self.cursor_range.end, &mut pattern, loc)?
+            .ok_or_else(|| self.failure(End::Front, Direction::Forward, loc, &pattern))?;
+        self.cursor_range.end += by;
+        Ok(self)
+    }
+
+    pub fn front_forward_or(
+        &mut self,
+        pattern: impl Pattern,
+        loc: PatternLoc,
+        fallback: FallBack,
+    ) -> Result<&mut Self, io::Error> {
+        match self.front_forward(pattern, loc) {
+            Ok(_) => (),
+            Err(StreamError::Io(err)) => return Err(err),
+            Err(StreamError::Cursor(_)) => match fallback {
+                FallBack::ToTheEnd => {
+                    while self.fill_more()? {}
+                    self.cursor_range.end = self.buffered_end();
+                }
+                FallBack::StayAtBeginning => (),
+            },
+        }
+        Ok(self)
+    }
+
+    pub fn back_forward(
+        &mut self,
+        mut pattern: impl Pattern,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+    ) -> Result<&mut Self, StreamError> {
+        let found = match inward_strategy {
+            InwardStrategy::CursorOnly => {
+                find_directional_offset(self.cursor(), pattern.by_ref(), loc, Direction::Forward)
+            }
+            InwardStrategy::WholeData => {
+                self.locate_forward(self.cursor_range.start, &mut pattern, loc)?
+            }
+        };
+        let by = found.ok_or_else(|| self.failure(End::Back, Direction::Forward, loc, &pattern))?;
+        self.cursor_range.start += by;
+        if self.cursor_range.start > self.cursor_range.end {
+            #[cfg(debug_assertions)]
+            if let InwardStrategy::CursorOnly = inward_strategy {
+                panic!("Cannot move past the other end of the cursor!")
+            }
+            self.front_to_back();
+        }
+        Ok(self)
+    }
+
+    pub fn back_forward_by(
+        &mut self,
+        by: usize,
+        inward_strategy: InwardStrategy,
+    ) -> io::Result<&mut Self> {
+        if let InwardStrategy::WholeData = inward_strategy {
+            self.fill_to(self.cursor_range.start, by)?;
+        }
+        let view = match inward_strategy {
+            InwardStrategy::CursorOnly => self.cursor(),
+            InwardStrategy::WholeData => {
+                self.buffered(self.cursor_range.start..self.buffered_end())
+            }
+        };
+        assert!(view.is_boundary(by));
+        self.cursor_range.start += by;
+        if self.cursor_range.start > self.cursor_range.end {
+            self.front_to_back();
+        }
+        Ok(self)
+    }
+
+    pub fn front_forward_by(&mut self, by: usize) -> io::Result<&mut Self> {
+        self.fill_to(self.cursor_range.end, by)?;
+        assert!(self.buffered_front_rem().is_boundary(by));
+        self.cursor_range.end += by;
+        Ok(self)
+    }
+
+    // What is still buffered before the back end.
+    fn retained_back_rem(&self) -> &str {
+        self.buffered(self.base..self.cursor_range.start)
+    }
+
+    // Nothing is read, as searching backward never needs what comes after.
+    pub fn back_backward(
+        &mut self,
+        mut pattern: impl Pattern,
+        loc: PatternLoc,
+    ) -> Result<&mut Self, StreamError> {
+        let view = self.retained_back_rem();
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Backward)
+            .ok_or_else(|| self.failure(End::Back, Direction::Backward, loc, &pattern))?;
+        self.cursor_range.start -= by;
+        Ok(self)
+    }
+
+    pub fn back_backward_by(&mut self, by: usize) -> &mut Self {
+        let view = self.retained_back_rem();
+        assert!(by <= view.len() && view.is_boundary(view.len() - by));
+        self.cursor_range.start -= by;
+        self
+    }
+
+    fn front_backward_view(&self, inward_strategy: InwardStrategy) -> &str {
+        match inward_strategy {
+            InwardStrategy::CursorOnly => self.cursor(),
+            InwardStrategy::WholeData => self.buffered(self.base..self.cursor_range.end),
+        }
+    }
+
+    pub fn front_backward(
+        &mut self,
+        mut pattern: impl Pattern,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+    ) -> Result<&mut Self, StreamError> {
+        let view = self.front_backward_view(inward_strategy);
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Backward)
+            .ok_or_else(|| self.failure(End::Front, Direction::Backward, loc, &pattern))?;
+        self.move_front_backward(by, inward_strategy);
+        Ok(self)
+    }
+
+    pub fn front_backward_by(&mut self, by: usize, inward_strategy: InwardStrategy) -> &mut Self {
+        let view = self.front_backward_view(inward_strategy);
+        assert!(by <= view.len() && view.is_boundary(view.len() - by));
+        self.move_front_backward(by, inward_strategy);
+        self
+    }
+
+    fn move_front_backward(&mut self, by: usize, inward_strategy: InwardStrategy) {
+        self.cursor_range.end -= by;
+        if self.cursor_range.end < self.cursor_range.start {
+            #[cfg(debug_assertions)]
+            if let InwardStrategy::CursorOnly = inward_strategy {
+                panic!("Cannot move past the other end of the cursor!")
+            }
+            self.back_to_front();
+        }
+    }
+
+    pub fn step(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<&mut Self, StreamError>,
+    ) -> Result<&str, StreamError> {
+        let saved_range = self.cursor_range.clone();
+        let saved_pin = self.pinned;
+        self.pinned = Some(saved_pin.map_or(saved_range.start, |pin| pin.min(saved_range.start)));
+        let res = f(self).map(|_| ());
+        self.pinned = saved_pin;
+        if let Err(err) = res {
+            self.cursor_range = saved_range;
+            return Err(err);
+        }
+        Ok(self.cursor())
+    }
+
+    // Yields the contents of each step as an owned string, since the buffer
+    // they are read from may be replaced by the next one. Ends at the first
+    // step that fails to move, but yields read errors.
+    pub fn iter_steps(
+        &mut self,
+        mut f: impl FnMut(&mut Self) -> Result<&mut Self, StreamError>,
+    ) -> impl Iterator<Item = io::Result<String>> {
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            self.back_to_front();
+            match self.step(&mut f) {
+                Ok(item) => Some(Ok(item.to_owned())),
+                Err(StreamError::Io(err)) => {
+                    done = true;
+                    Some(Err(err))
+                }
+                Err(StreamError::Cursor(_)) => None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::BufReader;
+
+    use super::*;
+
+    fn tiny_chunks(data: &str) -> StreamCursor<BufReader<&[u8]>> {
+        StreamCursor::new(BufReader::with_capacity(3, data.as_bytes()))
+    }
+
+    #[test]
+    fn test_patterns_across_chunk_boundaries() {
+        let mut cursor = tiny_chunks("ERROR: unflagazed plungus (at line 42)");
+        cursor
+            .back_forward(
+                "ERROR: ",
+                PatternLoc::BeginningOnce,
+                InwardStrategy::WholeData,
+            )
+            .unwrap()
+            .front_forward(" (", PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "unflagazed plungus");
+        cursor.back_to_front();
+        let line = cursor
+            .step(|c| {
+                c.back_forward(
+                    "line ",
+                    PatternLoc::FirstIncluded,
+                    InwardStrategy::WholeData,
+                )?
+                .front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningMany)
+            })
+            .unwrap();
+        assert_eq!(line, "42");
+        assert_eq!((cursor.back_offset(), cursor.front_offset()), (35, 37));
+    }
+
+    #[test]
+    fn test_matches_longer_than_chunks() {
+        let data = "unflagazed plungus; ababab!";
+        let mut cursor = tiny_chunks(data);
+        cursor
+            .front_forward("unflagazed plungus", PatternLoc::BeginningOnce)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "unflagazed plungus");
+        cursor.back_to_front();
+        cursor
+            .front_forward("; ", PatternLoc::BeginningOnce)
+            .unwrap()
+            .front_forward("ab", PatternLoc::BeginningMany)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "; ababab");
+
+        // A prefix of the pattern at the end of a chunk is not a failure yet.
+        let mut cursor = tiny_chunks("unflagazed plungus");
+        cursor
+            .front_forward("unflagazed plungus", PatternLoc::BeginningOnce)
+            .unwrap();
+        let mut cursor = tiny_chunks("unflagazed plunger, unflagazed plungus");
+        let err = cursor
+            .front_forward("unflagazed plungus", PatternLoc::BeginningOnce)
+            .unwrap_err();
+        assert!(matches!(err, StreamError::Cursor(_)));
+        cursor
+            .front_forward("unflagazed plungus", PatternLoc::FirstIncluded)
+            .unwrap();
+        assert!(cursor.is_at_eof());
+    }
+
+    #[test]
+    fn test_failed_prefix_does_not_read_ahead() {
+        let data = format!("WARN: x\n{}", "y".repeat(100_000));
+        let mut cursor = tiny_chunks(&data);
+        assert!(
+            cursor
+                .front_forward("ERROR: ", PatternLoc::BeginningOnce)
+                .is_err()
+        );
+        assert!(cursor.buf.len() < 16);
+        cursor
+            .front_forward("WARN: x", PatternLoc::BeginningMany)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "WARN: x");
+        assert!(cursor.buf.len() < 16);
+        cursor
+            .front_forward(['\n', ' '], PatternLoc::BeginningMany)
+            .unwrap();
+        assert!(cursor.buf.len() < 16);
+    }
+
+    #[test]
+    fn test_backward_moves_and_steps() {
+        let mut cursor = tiny_chunks("/var/log/app.log 1,22,333;");
+        cursor
+            .front_forward(' ', PatternLoc::FirstExcluded)
+            .unwrap()
+            .front_backward('/', PatternLoc::FirstIncluded, InwardStrategy::CursorOnly)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "/var/log");
+        cursor.back_to_front();
+        cursor
+            .back_backward('/', PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "log");
+        cursor
+            .back_backward_by(5)
+            .front_backward_by(4, InwardStrategy::CursorOnly);
+        assert_eq!(cursor.cursor(), "/var");
+
+        cursor.back_to_front();
+        cursor
+            .front_forward_by(13)
+            .unwrap()
+            .back_forward_by(13, InwardStrategy::CursorOnly)
+            .unwrap();
+        assert_eq!(cursor.back_offset(), 17);
+        let numbers: Vec<String> = cursor
+            .iter_steps(|c| {
+                c.front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningOnce)?
+                    .front_forward(|c: char| c.is_ascii_digit(), PatternLoc::BeginningMany)?
+                    .front_forward_or(',', PatternLoc::BeginningOnce, FallBack::StayAtBeginning)?;
+                Ok(c)
+            })
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(numbers, ["1,", "22,", "333"]);
+    }
+
+    #[test]
+    fn test_memory_stays_bounded() {
+        let data = "item;".repeat(1000);
+        let mut cursor = tiny_chunks(&data);
+        let mut count = 0;
+        while cursor.front_forward(';', PatternLoc::FirstExcluded).is_ok() {
+            assert_eq!(cursor.cursor(), "item");
+            cursor.back_to_front();
+            cursor
+                .front_forward(';', PatternLoc::BeginningOnce)
+                .unwrap();
+            cursor.back_to_front();
+            count += 1;
+            assert!(cursor.buf.len() < 24);
+        }
+        assert_eq!(count, 1000);
+        assert!(cursor.is_at_eof());
+    }
+
+    #[test]
+    fn test_last_occurrence_reads_to_the_end() {
+        let mut cursor = tiny_chunks("a/b/c/file.rs");
+        cursor.front_forward('/', PatternLoc::EndOfLast).unwrap();
+        assert_eq!(cursor.cursor(), "a/b/c/");
+    }
+
+    #[test]
+    fn test_fallback_reads_to_the_end() {
+        let mut cursor = tiny_chunks("no terminator here");
+        cursor
+            .front_forward_or(';', PatternLoc::FirstExcluded, FallBack::ToTheEnd)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "no terminator here");
+        let mut cursor = tiny_chunks("a;b");
+        cursor
+            .front_forward_or(';', PatternLoc::FirstExcluded, FallBack::ToTheEnd)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "a");
+    }
+
+    #[test]
+    fn test_multibyte_chars_split_between_chunks() {
+        let mut cursor = tiny_chunks("ééé!");
+        cursor
+            .front_forward('!', PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "ééé");
+    }
+
+    #[test]
+    fn test_failed_step_rolls_back_even_after_refills() {
+        let mut cursor = tiny_chunks("key = value; other");
+        let err = cursor
+            .step(|c| {
+                c.front_forward(';', PatternLoc::FirstIncluded)?
+                    .front_forward('#', PatternLoc::FirstExcluded)
+            })
+            .unwrap_err();
+        assert!(matches!(err, StreamError::Cursor(_)));
+        assert_eq!(cursor.cursor(), "");
+        cursor
+            .front_forward(" = ", PatternLoc::FirstExcluded)
+            .unwrap();
+        assert_eq!(cursor.cursor(), "key");
+    }
+
+    #[test]
+    fn test_invalid_utf8_is_an_io_error() {
+        let mut cursor = StreamCursor::new(&b"ab\xffcd"[..]);
+        let err = cursor
+            .front_forward('d', PatternLoc::FirstExcluded)
+            .unwrap_err();
+        assert!(matches!(err, StreamError::Io(err) if err.kind() == io::ErrorKind::InvalidData));
+    }
+}
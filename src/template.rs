@@ -0,0 +1,257 @@
+// Runtime support for the code `template!` and `#[derive(Parse)]` expand to.
+// Every step starts and ends with an empty cursor.
+
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{Context, CursorError, FallBack, InwardStrategy, ParseCursor, PatternLoc};
+
+pub fn literal(cursor: &mut ParseCursor<'_>, literal: &str) -> Result<(), CursorError> {
+    cursor.back_forward(
+        literal,
+        PatternLoc::BeginningOnce,
+        InwardStrategy::WholeData,
+    )?;
+    Ok(())
+}
+
+// Moves the front end to the closest of the literals that may follow the
+// hole, or to the end of the data when nothing has to follow it.
+pub fn hole(
+    cursor: &mut ParseCursor<'_>,
+    name: &str,
+    follow: &[&str],
+    to_end: bool,
+) -> Result<(), CursorError> {
+    let rem = cursor.front_rem();
+    match follow.iter().filter_map(|literal| rem.find(literal)).min() {
+        Some(by) => {
+            cursor.front_forward_by(by);
+        }
+        None if to_end => {
+            cursor.front_forward_by(rem.len());
+        }
+        None => {
+            cursor
+                .front_forward(follow[0], PatternLoc::FirstExcluded)
+                .with_context(|| format!("reading template hole `{name}`"))?;
+        }
+    }
+    Ok(())
+}
+
+pub fn text<'a>(cursor: &mut ParseCursor<'a>) -> &'a str {
+    let text = cursor.cursor();
+    cursor.back_to_front();
+    text
+}
+
+pub fn value<T>(cursor: &mut ParseCursor<'_>) -> Result<T, CursorError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let value = cursor.parse()?;
+    cursor.back_to_front();
+    Ok(value)
+}
+
+pub fn parse<T>(cursor: &mut ParseCursor<'_>, name: &str) -> Result<T, CursorError>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    value(cursor).with_context(|| format!("reading template hole `{name}`"))
+}
+
+pub struct FieldSpec<'s> {
+    pub name: &'s str,
+    pub prefix: Option<&'s str>,
+    pub terminator: Option<&'s str>,
+    pub or_end: bool,
+    pub suffix: Option<&'s str>,
+}
+
+pub fn field<'a, T>(
+    cursor: &mut ParseCursor<'a>,
+    spec: &FieldSpec<'_>,
+    value: impl FnOnce(&mut ParseCursor<'a>) -> Result<T, CursorError>,
+) -> Result<T, CursorError> {
+    let read = || {
+        if let Some(prefix) = spec.prefix {
+            literal(cursor, prefix)?;
+        }
+        match (spec.terminator, spec.or_end) {
+            (Some(terminator), true) => {
+                cursor.front_forward_or(terminator, PatternLoc::FirstExcluded, FallBack::ToTheEnd);
+            }
+            (Some(terminator), false) => {
+                cursor.front_forward(terminator, PatternLoc::FirstExcluded)?;
+            }
+            (None, true) => {
+                cursor.front_forward_by(cursor.front_rem().len());
+            }
+            (None, false) => (),
+        }
+        let value = value(cursor)?;
+        cursor.back_to_front();
+        if let Some(suffix) = spec.suffix {
+            literal(cursor, suffix)?;
+        }
+        Ok(value)
+    };
+    read().with_context(|| format!("reading field `{}`", spec.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Parse, ParseCursor, PatternLoc, template};
+
+    #[derive(Debug, PartialEq)]
+    struct ErrorLog<'a> {
+        msg: &'a str,
+        file: Option<&'a str>,
+        line: Option<u64>,
+        col: Option<u64>,
+    }
+
+    #[test]
+    fn test_template_into_struct() {
+        let parse = |input| {
+            let mut cursor = ParseCursor::new_empty_start(input);
+            template!(
+                cursor,
+                "ERROR: {msg}[ ({file} at line {line:u64}[, column {col:u64}])]" => ErrorLog
+            )
+        };
+        assert_eq!(
+            parse("ERROR: bazoombled (/etc/baz at line 3, column 6)"),
+            Ok(ErrorLog {
+                msg: "bazoombled",
+                file: Some("/etc/baz"),
+                line: Some(3),
+                col: Some(6),
+            })
+        );
+        assert_eq!(
+            parse("ERROR: misplumbed (/etc/baz at line 0)"),
+            Ok(ErrorLog {
+                msg: "misplumbed",
+                file: Some("/etc/baz"),
+                line: Some(0),
+                col: None,
+            })
+        );
+        assert_eq!(
+            parse("ERROR: fluxmoxxed"),
+            Ok(ErrorLog {
+                msg: "fluxmoxxed",
+                file: None,
+                line: None,
+                col: None,
+            })
+        );
+        assert!(parse("WARNING: fluxmoxxed").is_err());
+    }
+
+    #[test]
+    fn test_template_into_tuple() {
+        let mut cursor = ParseCursor::new_empty_start("k=v; {x}=12; rest");
+        let first = template!(cursor, "{key}={value}; ").unwrap();
+        assert_eq!(first, ("k", "v"));
+        let second = template!(&mut cursor, "{{{_}}}={n:i32};").unwrap();
+        assert_eq!(second, (12,));
+        assert_eq!(cursor.front_rem(), " rest");
+
+        let err = template!(cursor, " {word}!").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected '!' while reading template hole `word` (FirstExcluded moving front end forward at offset 13)"
+        );
+        assert_eq!(cursor.front_rem(), " rest");
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    struct Location<'a> {
+        #[parse(terminator = " at line ")]
+        file: &'a str,
+        #[parse(prefix = " at line ")]
+        line: u64,
+        #[parse(prefix = ", column ")]
+        column: u64,
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    struct DerivedLog<'a> {
+        #[parse(prefix = "ERROR: ", terminator = " (", or_end)]
+        msg: &'a str,
+        #[parse(prefix = " (", suffix = ")", optional)]
+        loc: Option<Location<'a>>,
+        #[parse(prefix = " (", terminator = ")", suffix = ")", optional)]
+        hint: Option<&'a str>,
+    }
+
+    #[test]
+    fn test_derive_parse() {
+        let parse = |input| ParseCursor::new_empty_start(input).take::<DerivedLog>();
+        assert_eq!(
+            parse("ERROR: unflagazed (/file at line 42, column 67) (was flagazed)"),
+            Ok(DerivedLog {
+                msg: "unflagazed",
+                loc: Some(Location {
+                    file: "/file",
+                    line: 42,
+                    column: 67,
+                }),
+                hint: Some("was flagazed"),
+            })
+        );
+        assert_eq!(
+            parse("ERROR: fluxmoxxed (very bad!)"),
+            Ok(DerivedLog {
+                msg: "fluxmoxxed",
+                loc: None,
+                hint: Some("very bad!"),
+            })
+        );
+        assert_eq!(
+            parse("ERROR: plain"),
+            Ok(DerivedLog {
+                msg: "plain",
+                loc: None,
+                hint: None,
+            })
+        );
+        let err = parse("WARNING: plain").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected 'ERROR: ' while reading field `msg` (BeginningOnce moving back end forward at offset 0)"
+        );
+    }
+
+    #[derive(Debug, PartialEq, Parse)]
+    struct Pair {
+        #[parse(terminator = "=")]
+        key: char,
+        #[parse(prefix = "=")]
+        value: i8,
+    }
+
+    #[test]
+    fn test_derive_parse_typed_fields() {
+        let mut cursor = ParseCursor::new_empty_start("a=-3,b=x");
+        assert_eq!(
+            cursor.take::<Pair>(),
+            Ok(Pair {
+                key: 'a',
+                value: -3
+            })
+        );
+        cursor
+            .front_forward(',', PatternLoc::BeginningOnce)
+            .unwrap();
+        let err = cursor.take::<Pair>().unwrap_err();
+        assert_eq!(err.offset(), 7);
+        assert_eq!(err.context().next(), Some("reading field `value`"));
+    }
+}
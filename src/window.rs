@@ -0,0 +1,346 @@
+use std::ops::Range;
+
+use crate::{
+    CursorError, Direction, End, FallBack, Haystack, InwardStrategy, ParseCursor, Pattern,
+    PatternLoc, find_directional_offset,
+};
+
+// A pattern that never matches, the stop of windows that only limit distance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Never;
+
+impl<H: ?Sized + Haystack> Pattern<H> for Never {
+    fn find_in(&mut self, _haystack: &H) -> Option<Range<usize>> {
+        None
+    }
+
+    fn rfind_in(&mut self, _haystack: &H) -> Option<Range<usize>> {
+        None
+    }
+
+    fn prefix_len(&mut self, _haystack: &H) -> Option<usize> {
+        None
+    }
+
+    fn suffix_len(&mut self, _haystack: &H) -> Option<usize> {
+        None
+    }
+
+    fn describe(&self) -> String {
+        "nothing".to_owned()
+    }
+}
+
+// How far from the end being moved the `*_within` moves may look: at most
+// `max_bytes`, and never past the nearest `stop`. Matches have to fit
+// entirely in the window, and `FallBack::ToTheEnd` goes to its edge.
+#[derive(Clone, Copy, Debug)]
+pub struct Window<S = Never> {
+    max_bytes: Option<usize>,
+    stop: S,
+}
+
+impl Window {
+    pub fn bytes(max_bytes: usize) -> Self {
+        Self {
+            max_bytes: Some(max_bytes),
+            stop: Never,
+        }
+    }
+}
+
+impl<S> Window<S> {
+    pub fn until(stop: S) -> Self {
+        Self {
+            max_bytes: None,
+            stop,
+        }
+    }
+
+    pub fn max_bytes(self, max_bytes: usize) -> Self {
+        Self {
+            max_bytes: Some(max_bytes),
+            ..self
+        }
+    }
+
+    // Length of the part of `view` a search in `direction` may look at,
+    // counted from the start of `view` going forward and from its end going
+    // backward.
+    fn limit<H>(&mut self, view: &H, direction: Direction) -> usize
+    where
+        H: ?Sized + Haystack,
+        S: Pattern<H>,
+    {
+        let len = view.len();
+        let mut limit = self.max_bytes.map_or(len, |max| max.min(len));
+        match direction {
+            Direction::Forward => {
+                while !view.is_boundary(limit) {
+                    limit -= 1;
+                }
+                if let Some(stop) = self.stop.find_in(view.slice(0..limit)) {
+                    limit = stop.start;
+                }
+            }
+            Direction::Backward => {
+                while !view.is_boundary(len - limit) {
+                    limit -= 1;
+                }
+                if let Some(stop) = self.stop.rfind_in(view.slice(len - limit..len)) {
+                    limit = len - (len - limit + stop.end);
+                }
+            }
+        }
+        limit
+    }
+}
+
+fn windowed<H: ?Sized + Haystack>(view: &H, limit: usize, direction: Direction) -> &H {
+    match direction {
+        Direction::Forward => view.slice(0..limit),
+        Direction::Backward => view.slice(view.len() - limit..view.len()),
+    }
+}
+
+impl<'a, H: ?Sized + Haystack> ParseCursor<'a, H> {
+    pub fn front_forward_within<S: Pattern<H>>(
+        &mut self,
+        mut pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        mut window: Window<S>,
+    ) -> Result<&mut Self, CursorError> {
+        let view = self.front_rem();
+        let view = windowed(
+            view,
+            window.limit(view, Direction::Forward),
+            Direction::Forward,
+        );
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Forward)
+            .ok_or_else(|| self.failure(End::Front, Direction::Forward, loc, &pattern))?;
+        self.move_front_forward(by);
+        Ok(self)
+    }
+
+    pub fn front_forward_within_or<S: Pattern<H>>(
+        &mut self,
+        pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        mut window: Window<S>,
+        fallback: FallBack,
+    ) -> &mut Self {
+        let limit = window.limit(self.front_rem(), Direction::Forward);
+        if self
+            .front_forward_within(pattern, loc, window.max_bytes(limit))
+            .is_err()
+        {
+            match fallback {
+                FallBack::ToTheEnd => self.move_front_forward(limit),
+                FallBack::StayAtBeginning => (),
+            }
+        }
+        self
+    }
+
+    pub fn back_backward_within<S: Pattern<H>>(
+        &mut self,
+        mut pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        mut window: Window<S>,
+    ) -> Result<&mut Self, CursorError> {
+        let view = self.back_rem();
+        let view = windowed(
+            view,
+            window.limit(view, Direction::Backward),
+            Direction::Backward,
+        );
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Backward)
+            .ok_or_else(|| self.failure(End::Back, Direction::Backward, loc, &pattern))?;
+        self.move_back_backward(by);
+        Ok(self)
+    }
+
+    pub fn back_backward_within_or<S: Pattern<H>>(
+        &mut self,
+        pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        mut window: Window<S>,
+        fallback: FallBack,
+    ) -> &mut Self {
+        let limit = window.limit(self.back_rem(), Direction::Backward);
+        if self
+            .back_backward_within(pattern, loc, window.max_bytes(limit))
+            .is_err()
+        {
+            match fallback {
+                FallBack::ToTheEnd => self.move_back_backward(limit),
+                FallBack::StayAtBeginning => (),
+            }
+        }
+        self
+    }
+
+    pub fn front_backward_within<S: Pattern<H>>(
+        &mut self,
+        mut pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+        mut window: Window<S>,
+    ) -> Result<&mut Self, CursorError> {
+        let view = self.front_backward_view(inward_strategy);
+        let view = windowed(
+            view,
+            window.limit(view, Direction::Backward),
+            Direction::Backward,
+        );
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Backward)
+            .ok_or_else(|| self.failure(End::Front, Direction::Backward, loc, &pattern))?;
+        self.move_front_backward(by, inward_strategy);
+        Ok(self)
+    }
+
+    pub fn front_backward_within_or<S: Pattern<H>>(
+        &mut self,
+        pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+        mut window: Window<S>,
+        fallback: FallBack,
+    ) -> &mut Self {
+        let view = self.front_backward_view(inward_strategy);
+        let limit = window.limit(view, Direction::Backward);
+        if self
+            .front_backward_within(pattern, loc, inward_strategy, window.max_bytes(limit))
+            .is_err()
+        {
+            match fallback {
+                FallBack::ToTheEnd => self.move_front_backward(limit, inward_strategy),
+                FallBack::StayAtBeginning => (),
+            }
+        }
+        self
+    }
+
+    pub fn back_forward_within<S: Pattern<H>>(
+        &mut self,
+        mut pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+        mut window: Window<S>,
+    ) -> Result<&mut Self, CursorError> {
+        let view = self.back_forward_view(inward_strategy);
+        let view = windowed(
+            view,
+            window.limit(view, Direction::Forward),
+            Direction::Forward,
+        );
+        let by = find_directional_offset(view, pattern.by_ref(), loc, Direction::Forward)
+            .ok_or_else(|| self.failure(End::Back, Direction::Forward, loc, &pattern))?;
+        self.move_back_forward(by, inward_strategy);
+        Ok(self)
+    }
+
+    pub fn back_forward_within_or<S: Pattern<H>>(
+        &mut self,
+        pattern: impl Pattern<H>,
+        loc: PatternLoc,
+        inward_strategy: InwardStrategy,
+        mut window: Window<S>,
+        fallback: FallBack,
+    ) -> &mut Self {
+        let view = self.back_forward_view(inward_strategy);
+        let limit = window.limit(view, Direction::Forward);
+        if self
+            .back_forward_within(pattern, loc, inward_strategy, window.max_bytes(limit))
+            .is_err()
+        {
+            match fallback {
+                FallBack::ToTheEnd => self.move_back_forward(limit, inward_strategy),
+                FallBack::StayAtBeginning => (),
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stop_pattern_is_not_crossed() {
+        let data = "(abc\n(def) (ghi)";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        let err = cursor
+            .front_forward_within(')', PatternLoc::FirstExcluded, Window::until('\n'))
+            .unwrap_err();
+        assert_eq!(err.offset(), 0);
+        cursor.front_forward_within_or(
+            ')',
+            PatternLoc::FirstExcluded,
+            Window::until('\n'),
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.cursor(), "(abc");
+
+        let mut cursor = ParseCursor::new_empty_end(data);
+        cursor
+            .back_backward_within('(', PatternLoc::FirstExcluded, Window::until('\n'))
+            .unwrap();
+        assert_eq!(cursor.cursor(), "ghi)");
+        cursor.back_backward_within_or(
+            ']',
+            PatternLoc::LastExcluded,
+            Window::until('\n'),
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.back_rem(), "(abc\n");
+    }
+
+    #[test]
+    fn test_max_bytes_window() {
+        let data = "key=été;";
+        let mut cursor = ParseCursor::new_empty_start(data);
+        assert!(
+            cursor
+                .front_forward_within(';', PatternLoc::FirstExcluded, Window::bytes(8))
+                .is_err()
+        );
+        // The window shrinks to a char boundary instead of splitting the `é`.
+        cursor.front_forward_within_or(
+            ';',
+            PatternLoc::FirstExcluded,
+            Window::bytes(8),
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.cursor(), "key=ét");
+        cursor.front_to_back();
+        assert!(
+            cursor
+                .front_forward_within(
+                    ';',
+                    PatternLoc::FirstIncluded,
+                    Window::until('=').max_bytes(100),
+                )
+                .is_err()
+        );
+
+        let mut cursor = ParseCursor::new_full(data);
+        cursor
+            .front_backward_within(
+                '=',
+                PatternLoc::FirstExcluded,
+                InwardStrategy::CursorOnly,
+                Window::bytes(3),
+            )
+            .unwrap_err();
+        cursor.back_forward_within_or(
+            '=',
+            PatternLoc::FirstIncluded,
+            InwardStrategy::CursorOnly,
+            Window::bytes(2),
+            FallBack::ToTheEnd,
+        );
+        assert_eq!(cursor.cursor(), "y=été;");
+    }
+}